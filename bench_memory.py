@@ -0,0 +1,92 @@
+import sys
+import os
+import resource
+import subprocess
+import time
+
+# Measures the runtime and the peak memory (maximum resident set size) of one or more
+# executables on every model of a directory.
+#
+# Usage: bench_memory.py [timeout in seconds] [model_directory] [executable]...
+#
+# Prints a CSV table to the standard output: one line per model, with the runtime,
+# the peak memory and the last line of output of every executable (or TIMEOUT/FAIL),
+# followed by the totals over the models completed by all the executables.
+
+
+# Runs the command and returns (status, runtime in seconds, peak memory in MB, last line).
+# Peak memory of the (only) child process is read from `getrusage` of a fresh process,
+# so that the peaks of the previous runs do not interfere.
+def MEASURE(command, cut_off):
+    read_end, write_end = os.pipe()
+    pid = os.fork()
+    if pid == 0:
+        os.close(read_end)
+        start = time.time()
+        try:
+            result = subprocess.run(
+                command, capture_output=True, text=True, timeout=cut_off
+            )
+            status = "OK" if result.returncode == 0 else "FAIL"
+            lines = result.stdout.strip().splitlines()
+            last = lines[-1] if len(lines) > 0 else ""
+        except subprocess.TimeoutExpired:
+            status = "TIMEOUT"
+            last = ""
+        elapsed = time.time() - start
+        peak = resource.getrusage(resource.RUSAGE_CHILDREN).ru_maxrss / 1024
+        os.write(write_end, f"{status}\t{elapsed:.2f}\t{peak:.0f}\t{last}".encode())
+        os._exit(0)
+
+    os.close(write_end)
+    with os.fdopen(read_end) as f:
+        status, elapsed, peak, last = f.read().split("\t", 3)
+    os.waitpid(pid, 0)
+    return status, float(elapsed), int(peak), last
+
+
+if __name__ == "__main__":
+    CUT_OFF = float(sys.argv[1])
+    BENCH_DIR = sys.argv[2]
+    EXECUTABLES = sys.argv[3:]
+
+    BENCHMARKS = sorted(filter(lambda it: it.endswith(".aeon"), os.listdir(BENCH_DIR)))
+
+    header = ["Benchmark"]
+    for executable in EXECUTABLES:
+        name = os.path.basename(executable)
+        header += [f"{name} Time[s]", f"{name} Memory[MB]", f"{name} Result"]
+    print(", ".join(header), flush=True)
+
+    TOTAL_TIME = [0.0] * len(EXECUTABLES)
+    TOTAL_MEMORY = [0] * len(EXECUTABLES)
+    MAX_MEMORY = [0] * len(EXECUTABLES)
+    COMPLETED = 0
+    for bench in BENCHMARKS:
+        results = [
+            MEASURE([executable, BENCH_DIR + "/" + bench], CUT_OFF)
+            for executable in EXECUTABLES
+        ]
+        row = [os.path.splitext(bench)[0]]
+        for status, elapsed, peak, last in results:
+            if status == "OK":
+                row += [f"{elapsed:.2f}", str(peak), f'"{last[:40]}"']
+            else:
+                row += [status, str(peak), ""]
+        print(", ".join(row), flush=True)
+
+        if all(status == "OK" for status, _, _, _ in results):
+            COMPLETED += 1
+            for i, (_, elapsed, peak, _) in enumerate(results):
+                TOTAL_TIME[i] += elapsed
+                TOTAL_MEMORY[i] += peak
+                MAX_MEMORY[i] = max(MAX_MEMORY[i], peak)
+
+    print()
+    print(f"Completed by all: {COMPLETED} of {len(BENCHMARKS)}")
+    for i, executable in enumerate(EXECUTABLES):
+        print(
+            f"{executable}: total time {TOTAL_TIME[i]:.1f} s, "
+            f"total peak memory {TOTAL_MEMORY[i]} MB, "
+            f"maximal peak memory {MAX_MEMORY[i]} MB"
+        )
@@ -0,0 +1,359 @@
+//! Analysis of the individual SCCs produced by the decomposition.
+
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// The behaviour of a single variable within a set of states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VariableActivity {
+    FixedTrue,
+    FixedFalse,
+    /// The variable takes both values (within an SCC, this means it keeps changing).
+    Oscillating,
+}
+
+/// Which variables are fixed and which oscillate within a (nonempty) set of states,
+/// typically an SCC.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OscillationProfile {
+    activity: Vec<(VariableId, VariableActivity)>,
+}
+
+impl OscillationProfile {
+    pub fn new<G: SymbolicTransitionSystem>(graph: &G, scc: &GraphColoredVertices) -> Self {
+        assert!(!scc.is_empty());
+
+        let activity = graph
+            .variables()
+            .into_iter()
+            .map(|var| {
+                let can_be_true = !scc.fix_network_variable(var, true).is_empty();
+                let can_be_false = !scc.fix_network_variable(var, false).is_empty();
+                let activity = match (can_be_true, can_be_false) {
+                    (true, true) => VariableActivity::Oscillating,
+                    (true, false) => VariableActivity::FixedTrue,
+                    (false, true) => VariableActivity::FixedFalse,
+                    (false, false) => unreachable!("the set is not empty"),
+                };
+                (var, activity)
+            })
+            .collect();
+
+        OscillationProfile { activity }
+    }
+
+    /// The activity of every network variable (in the order of the graph variables).
+    pub fn activity(&self) -> &[(VariableId, VariableActivity)] {
+        &self.activity
+    }
+
+    pub fn oscillating(&self) -> Vec<VariableId> {
+        self.activity
+            .iter()
+            .filter(|(_, activity)| *activity == VariableActivity::Oscillating)
+            .map(|(var, _)| *var)
+            .collect()
+    }
+
+    /// The smallest subspace enclosing the set, given by the values of the fixed variables
+    /// (in the format accepted by `SymbolicAsyncGraph::mk_subspace`).
+    pub fn enclosing_subspace(&self) -> Vec<(VariableId, bool)> {
+        self.activity
+            .iter()
+            .filter_map(|(var, activity)| match activity {
+                VariableActivity::FixedTrue => Some((*var, true)),
+                VariableActivity::FixedFalse => Some((*var, false)),
+                VariableActivity::Oscillating => None,
+            })
+            .collect()
+    }
+}
+
+/// Writes the enclosing subspace, one character per variable: `1`/`0` for fixed variables
+/// and `*` for the oscillating ones.
+impl Display for OscillationProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (_, activity) in &self.activity {
+            let symbol = match activity {
+                VariableActivity::FixedTrue => '1',
+                VariableActivity::FixedFalse => '0',
+                VariableActivity::Oscillating => '*',
+            };
+            write!(f, "{symbol}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The shape of an SCC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SccShape {
+    /// A single state without any successor.
+    FixedPoint,
+    /// A single state that is not a fixed point.
+    Trivial,
+    /// Every state has exactly one successor within the SCC.
+    SimpleCycle,
+    /// Any other (non-trivial) SCC.
+    Complex,
+}
+
+/// The shape of an SCC and whether it is terminal (no transition leaves it, i.e. it is an
+/// attractor) or transient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SccClassification {
+    pub shape: SccShape,
+    pub terminal: bool,
+}
+
+impl SccClassification {
+    /// Classifies the `scc`, which must be an SCC of the `graph` (e.g. produced by
+    /// [crate::chain::chain]).
+    pub fn new<G: SymbolicTransitionSystem>(graph: &G, scc: &GraphColoredVertices) -> Self {
+        assert!(!scc.is_empty());
+
+        let successors = graph.post(scc);
+        let terminal = successors.is_subset(scc);
+
+        let shape = if scc.is_singleton() {
+            match successors.is_empty() {
+                true => SccShape::FixedPoint,
+                false => SccShape::Trivial,
+            }
+        } else {
+            match branching_within(graph, scc).is_empty() {
+                true => SccShape::SimpleCycle,
+                false => SccShape::Complex,
+            }
+        };
+
+        SccClassification { shape, terminal }
+    }
+}
+
+/// Writes the classification as e.g. `terminal cycle` or `transient complex`.
+impl Display for SccClassification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let terminal = match self.terminal {
+            true => "terminal",
+            false => "transient",
+        };
+        let shape = match self.shape {
+            SccShape::FixedPoint => "fixed-point",
+            SccShape::Trivial => "trivial",
+            SccShape::SimpleCycle => "cycle",
+            SccShape::Complex => "complex",
+        };
+        write!(f, "{terminal} {shape}")
+    }
+}
+
+/// The states of `set` with at least two successors within `set`.
+///
+/// Two different successors differ in some variable, so a state branches iff, for some
+/// variable, it can reach both a state of `set` where the variable is true and one where
+/// it is false.
+fn branching_within<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    graph
+        .variables()
+        .into_iter()
+        .map(|var| {
+            let reach_true = graph.pre(&set.fix_network_variable(var, true));
+            let reach_false = graph.pre(&set.fix_network_variable(var, false));
+            reach_true.intersect(&reach_false)
+        })
+        .fold(graph.empty_colored_vertices().clone(), |acc, it| {
+            acc.union(&it)
+        })
+        .intersect(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Config;
+    use crate::chain::chain;
+    use crate::explicit::ExplicitAsyncGraph;
+    use crate::semantics::SymbolicRelationGraph;
+    use crate::test_utils::arb_network;
+    use crate::test_utils::cycle_network;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
+    use std::collections::HashSet;
+
+    #[test]
+    fn oscillation_profile_basic() {
+        // `A` oscillates on its own, `B` and `C` are stuck
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -| A
+            B -> B
+            C -> C
+            $A: !A
+            $B: B
+            $C: C
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+
+        let scc = graph.mk_subspace(&[(var_b, true), (var_c, false)]);
+        let profile = OscillationProfile::new(&graph, &scc);
+
+        assert_eq!(
+            profile.activity(),
+            &[
+                (var_a, VariableActivity::Oscillating),
+                (var_b, VariableActivity::FixedTrue),
+                (var_c, VariableActivity::FixedFalse),
+            ]
+        );
+        assert_eq!(profile.oscillating(), vec![var_a]);
+        assert_eq!(
+            profile.enclosing_subspace(),
+            vec![(var_b, true), (var_c, false)]
+        );
+        assert_eq!(graph.mk_subspace(&profile.enclosing_subspace()), scc);
+        assert_eq!(profile.to_string(), "*10");
+
+        // a single state is its own enclosing subspace
+        let state = graph.mk_subspace(&[(var_a, true), (var_b, true), (var_c, false)]);
+        let profile = OscillationProfile::new(&graph, &state);
+        assert!(profile.oscillating().is_empty());
+        assert_eq!(profile.to_string(), "110");
+    }
+
+    #[test]
+    fn oscillation_profile_synchronous_swap() {
+        // the synchronous swap: `A` and `B` swap values
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> B
+            B -> A
+            $A: B
+            $B: A
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicRelationGraph::synchronous(&bn).unwrap();
+
+        let sccs = chain(graph.clone(), Config::default()).collect::<Vec<_>>();
+        assert_eq!(sccs.len(), 1);
+
+        // the two states `01` and `10` are not enclosed by a smaller subspace than the whole space
+        let profile = OscillationProfile::new(&graph, &sccs[0]);
+        assert_eq!(profile.to_string(), "**");
+        assert!(profile.enclosing_subspace().is_empty());
+    }
+
+    #[test]
+    fn classification_basic() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+        let cycle = graph.mk_subspace(&[(var_c, false)]);
+        let fixed_point = graph.mk_subspace(&[(var_a, false), (var_b, false), (var_c, true)]);
+
+        let sccs = chain(graph.clone(), Config::default()).collect::<Vec<_>>();
+        assert_eq!(sccs, vec![cycle.clone()]);
+
+        let classification = SccClassification::new(&graph, &cycle);
+        assert_eq!(classification.shape, SccShape::SimpleCycle);
+        assert!(!classification.terminal);
+        assert_eq!(classification.to_string(), "transient cycle");
+
+        assert!(fixed_point.is_singleton());
+        let classification = SccClassification::new(&graph, &fixed_point);
+        assert_eq!(classification.shape, SccShape::FixedPoint);
+        assert!(classification.terminal);
+    }
+
+    #[test]
+    fn classification_synchronous_swap() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> B
+            B -> A
+            $A: B
+            $B: A
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicRelationGraph::synchronous(&bn).unwrap();
+
+        let sccs = chain(graph.clone(), Config::default()).collect::<Vec<_>>();
+        let classification = SccClassification::new(&graph, &sccs[0]);
+        assert_eq!(classification.shape, SccShape::SimpleCycle);
+        assert!(classification.terminal);
+
+        // the two constant states are fixed points
+        let cycle_free = graph.unit_colored_vertices().minus(&sccs[0]);
+        assert_eq!(cycle_free.exact_cardinality(), 2u32.into());
+        let fixed_point = cycle_free.pick_singleton();
+        let classification = SccClassification::new(&graph, &fixed_point);
+        assert_eq!(classification.shape, SccShape::FixedPoint);
+        assert!(classification.terminal);
+    }
+
+    /// Classifies an explicit SCC by counting the transitions.
+    fn explicit_classification(explicit: &ExplicitAsyncGraph, scc: &[usize]) -> SccClassification {
+        let members = scc.iter().copied().collect::<HashSet<_>>();
+        let terminal = scc.iter().all(|state| {
+            explicit
+                .successors(*state)
+                .iter()
+                .all(|it| members.contains(it))
+        });
+
+        let shape = if scc.len() == 1 {
+            match explicit.successors(scc[0]).is_empty() {
+                true => SccShape::FixedPoint,
+                false => SccShape::Trivial,
+            }
+        } else {
+            let in_scc_successors = |state: usize| {
+                explicit
+                    .successors(state)
+                    .iter()
+                    .filter(|it| members.contains(it))
+                    .count()
+            };
+            match scc.iter().all(|state| in_scc_successors(*state) == 1) {
+                true => SccShape::SimpleCycle,
+                false => SccShape::Complex,
+            }
+        };
+
+        SccClassification { shape, terminal }
+    }
+
+    proptest! {
+        #[test]
+        fn compare_classification_with_explicit_random(bn in arb_network(6)) {
+            let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+            let explicit = ExplicitAsyncGraph::new(&bn).unwrap();
+
+            for scc in explicit.scc_decomposition() {
+                let symbolic_scc = explicit.to_symbolic(&graph, &scc);
+                prop_assert_eq!(
+                    SccClassification::new(&graph, &symbolic_scc),
+                    explicit_classification(&explicit, &scc)
+                );
+            }
+        }
+    }
+}
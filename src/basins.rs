@@ -0,0 +1,222 @@
+//! Basins of attraction of the terminal SCCs (attractors) found by the decomposition.
+
+use crate::analysis::SccClassification;
+use crate::chain::bwd_saturation;
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+
+/// The basins of a list of attractors; `weak` and `strong` follow the order of the attractors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Basins {
+    /// The states that can reach the attractor (including the attractor itself).
+    pub weak: Vec<GraphColoredVertices>,
+    /// The states that can reach the attractor, but none of the other attractors.
+    pub strong: Vec<GraphColoredVertices>,
+    /// The states that can reach more than one of the attractors.
+    pub shared: GraphColoredVertices,
+}
+
+impl Basins {
+    /// Computes the basins of the given `attractors` of the `graph`.
+    ///
+    /// Every attractor must be closed under the transitions (a terminal SCC, or any union
+    /// of them, e.g. a set of fixed points). The list does not have to be complete: the strong
+    /// basins exclude the states that can reach any other attractor, listed or not (e.g. the
+    /// fixed points, which [crate::chain::chain] does not output).
+    pub fn new<G: SymbolicTransitionSystem>(
+        graph: &G,
+        attractors: &[GraphColoredVertices],
+    ) -> Self {
+        for attractor in attractors {
+            assert!(graph.post(attractor).is_subset(attractor));
+        }
+
+        let weak = attractors
+            .iter()
+            .map(|attractor| bwd_saturation(graph, attractor))
+            .collect::<Vec<_>>();
+
+        // every other attractor lies outside of the weak basin, so the states that can leave
+        // the weak basin are exactly those that can reach another attractor
+        let unit = graph.unit_colored_vertices();
+        let strong = weak
+            .iter()
+            .map(|weak| weak.minus(&bwd_saturation(graph, &unit.minus(weak))))
+            .collect::<Vec<_>>();
+
+        // the states in at least two of the weak basins
+        let empty = graph.empty_colored_vertices();
+        let (shared, _) = weak
+            .iter()
+            .fold((empty.clone(), empty.clone()), |(shared, seen), it| {
+                (shared.union(&seen.intersect(it)), seen.union(it))
+            });
+
+        Basins {
+            weak,
+            strong,
+            shared,
+        }
+    }
+}
+
+/// All the fixed points of the `graph`, i.e. the states without any successor.
+pub fn fixed_points<G: SymbolicTransitionSystem>(graph: &G) -> GraphColoredVertices {
+    let unit = graph.unit_colored_vertices();
+    unit.minus(&graph.pre(unit))
+}
+
+/// The terminal SCCs (attractors) among the given `sccs` of the `graph`.
+pub fn terminal_sccs<G: SymbolicTransitionSystem>(
+    graph: &G,
+    sccs: impl IntoIterator<Item = GraphColoredVertices>,
+) -> Vec<GraphColoredVertices> {
+    sccs.into_iter()
+        .filter(|scc| SccClassification::new(graph, scc).terminal)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Config;
+    use crate::chain::chain;
+    use crate::explicit::ExplicitAsyncGraph;
+    use crate::test_utils::arb_network;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
+
+    #[test]
+    fn basins_toggle_switch() {
+        // two fixed points `10` and `01`; `00` and `11` can go either way
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -| B
+            B -| A
+            $A: !B
+            $B: !A
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let state = |a, b| graph.mk_subspace(&[(var_a, a), (var_b, b)]);
+
+        let fixed = fixed_points(&graph);
+        assert_eq!(fixed, state(true, false).union(&state(false, true)));
+        assert!(chain(graph.clone(), Config::default()).next().is_none());
+
+        let attractors = [state(true, false), state(false, true)];
+        let basins = Basins::new(&graph, &attractors);
+
+        let undecided = state(false, false).union(&state(true, true));
+        assert_eq!(basins.weak[0], attractors[0].union(&undecided));
+        assert_eq!(basins.weak[1], attractors[1].union(&undecided));
+        assert_eq!(basins.strong, attractors.to_vec());
+        assert_eq!(basins.shared, undecided);
+
+        // the undecided states can still reach the other fixed point, even if it is not listed
+        let basins = Basins::new(&graph, &attractors[..1]);
+        assert_eq!(basins.weak[0], attractors[0].union(&undecided));
+        assert_eq!(basins.strong[0], attractors[0]);
+        assert!(basins.shared.is_empty());
+    }
+
+    #[test]
+    fn basins_terminal_cycle() {
+        // `A` oscillates unless `B` is set; `B` can be set, but never unset
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -| A
+            B -| A
+            B -> B
+            $A: !A & !B
+            $B: B
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+
+        let sccs = chain(graph.clone(), Config::default()).collect::<Vec<_>>();
+        let attractors = terminal_sccs(&graph, sccs);
+        assert_eq!(attractors, vec![graph.mk_subspace(&[(var_b, false)])]);
+
+        let fixed = fixed_points(&graph);
+        assert_eq!(fixed, graph.mk_subspace(&[(var_a, false), (var_b, true)]));
+
+        let basins = Basins::new(&graph, &[attractors[0].clone(), fixed.clone()]);
+        assert_eq!(basins.weak[0], attractors[0]);
+        assert_eq!(basins.strong[0], attractors[0]);
+        assert_eq!(basins.strong[1], graph.mk_subspace(&[(var_b, true)]));
+        assert!(basins.shared.is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn compare_basins_with_explicit_random(bn in arb_network(6)) {
+            let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+            let explicit = ExplicitAsyncGraph::new(&bn).unwrap();
+
+            // the terminal SCCs in the explicit graph
+            let explicit_attractors = explicit
+                .scc_decomposition()
+                .into_iter()
+                .filter(|scc| {
+                    scc.iter()
+                        .all(|state| explicit.successors(*state).iter().all(|it| scc.contains(it)))
+                })
+                .collect::<Vec<_>>();
+            let attractors = explicit_attractors
+                .iter()
+                .map(|scc| explicit.to_symbolic(&graph, scc))
+                .collect::<Vec<_>>();
+
+            let basins = Basins::new(&graph, &attractors);
+
+            // the states reaching every attractor, computed explicitly
+            let reaching = explicit_attractors
+                .iter()
+                .map(|scc| {
+                    (0..explicit.num_states())
+                        .filter(|state| explicit.reachable(*state).contains(&scc[0]))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            for (i, states) in reaching.iter().enumerate() {
+                prop_assert_eq!(&basins.weak[i], &explicit.to_symbolic(&graph, states));
+
+                let only_this = states
+                    .iter()
+                    .filter(|state| {
+                        reaching
+                            .iter()
+                            .enumerate()
+                            .all(|(j, other)| i == j || !other.contains(state))
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+                prop_assert_eq!(&basins.strong[i], &explicit.to_symbolic(&graph, &only_this));
+            }
+
+            // the strong basins do not depend on the other attractors being listed
+            if let Some((_, listed)) = attractors.split_last() {
+                let partial = Basins::new(&graph, listed);
+                prop_assert_eq!(&partial.strong[..], &basins.strong[..listed.len()]);
+            }
+
+            // every state reaches some attractor, the basins thus cover the whole space
+            let covered = basins
+                .strong
+                .iter()
+                .fold(basins.shared.clone(), |acc, it| acc.union(it));
+            prop_assert_eq!(&covered, graph.unit_colored_vertices());
+        }
+    }
+}
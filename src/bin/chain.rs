@@ -1,3 +1,4 @@
+use biodivine_lib_algo_scc::analysis::OscillationProfile;
 use biodivine_lib_algo_scc::chain::Config;
 use biodivine_lib_algo_scc::chain::Strategy;
 use biodivine_lib_algo_scc::chain::chain;
@@ -6,7 +7,8 @@ use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
 
 fn main() {
     let args = std::env::args().collect::<Vec<_>>();
-    assert_eq!(args.len(), 2);
+    assert!(args.len() == 2 || (args.len() == 3 && args[2] == "--profiles"));
+    let profiles = args.len() == 3;
 
     let bn = BooleanNetwork::try_from_file(&args[1]).unwrap();
     let bn = bn.inline_constants(true, true);
@@ -15,7 +17,7 @@ fn main() {
     println!("Loaded BN with {} variables.", bn.num_vars());
 
     let mut scc_list = chain(
-        graph,
+        graph.clone(),
         Config {
             strategy: Strategy::Chain,
             ..Default::default()
@@ -31,6 +33,9 @@ fn main() {
     for scc in scc_list.iter().rev().take(100) {
         if !scc.is_singleton() {
             print!(", {}", scc.exact_cardinality());
+            if profiles {
+                print!(" {}", OscillationProfile::new(&graph, scc));
+            }
         }
     }
     println!();
@@ -0,0 +1,56 @@
+use biodivine_lib_algo_scc::analysis::OscillationProfile;
+use biodivine_lib_algo_scc::chain::TrimLvl;
+use biodivine_lib_algo_scc::checkpoint::Checkpoint;
+use biodivine_lib_algo_scc::checkpoint::chain_from_checkpoint;
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use std::path::Path;
+
+/// The number of decomposition steps between two checkpoints.
+const INTERVAL: usize = 100;
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    assert!(args.len() == 3 || (args.len() == 4 && args[3] == "--profiles"));
+    let profiles = args.len() == 4;
+
+    let bn = BooleanNetwork::try_from_file(&args[1]).unwrap();
+    let bn = bn.inline_constants(true, true);
+    let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+
+    println!("Loaded BN with {} variables.", bn.num_vars());
+
+    let path = Path::new(&args[2]);
+    let checkpoint = match path.exists() {
+        true => {
+            let checkpoint = Checkpoint::load(&graph, path).unwrap();
+            println!(
+                "Resuming from {} ({} SCCs found, {} subgraphs remaining).",
+                path.display(),
+                checkpoint.output().len(),
+                checkpoint.remaining()
+            );
+            checkpoint
+        }
+        false => Checkpoint::new(&graph, TrimLvl::None),
+    };
+
+    let mut scc_list = chain_from_checkpoint(&graph, checkpoint, INTERVAL, |checkpoint| {
+        checkpoint.save(path).unwrap()
+    });
+    scc_list.sort_by_key(|it| it.exact_cardinality());
+
+    let trivial = scc_list.iter().filter(|it| it.is_singleton()).count();
+
+    println!("all_scc, trivial_scc, sizes...");
+    print!("{}, {}", scc_list.len(), trivial);
+    for scc in scc_list.iter().rev().take(100) {
+        if !scc.is_singleton() {
+            print!(", {}", scc.exact_cardinality());
+            if profiles {
+                print!(" {}", OscillationProfile::new(&graph, scc));
+            }
+        }
+    }
+    println!();
+}
@@ -0,0 +1,61 @@
+use biodivine_lib_algo_scc::analysis::OscillationProfile;
+use biodivine_lib_algo_scc::chain::Config;
+use biodivine_lib_algo_scc::chain::Strategy;
+use biodivine_lib_algo_scc::inputs::chain_per_input;
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    assert!(args.len() == 2 || (args.len() == 3 && args[2] == "--profiles"));
+    let profiles = args.len() == 3;
+
+    let bn = BooleanNetwork::try_from_file(&args[1]).unwrap();
+    let bn = bn.inline_constants(true, true);
+    let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+    let inputs = bn.inputs(true);
+
+    println!(
+        "Loaded BN with {} variables ({} inputs).",
+        bn.num_vars(),
+        inputs.len()
+    );
+
+    let mut total_scc = 0;
+    let mut total_trivial = 0;
+
+    println!("inputs, all_scc, trivial_scc, sizes...");
+    for (valuation, mut scc_list) in chain_per_input(
+        graph.clone(),
+        &inputs,
+        Config {
+            strategy: Strategy::Chain,
+            ..Default::default()
+        },
+    ) {
+        scc_list.sort_by_key(|it| it.exact_cardinality());
+
+        let trivial = scc_list.iter().filter(|it| it.is_singleton()).count();
+        total_scc += scc_list.len();
+        total_trivial += trivial;
+
+        let valuation = valuation
+            .iter()
+            .map(|(_, value)| if *value { '1' } else { '0' })
+            .collect::<String>();
+
+        print!("{}, {}, {}", valuation, scc_list.len(), trivial);
+        for scc in scc_list.iter().rev().take(100) {
+            if !scc.is_singleton() {
+                print!(", {}", scc.exact_cardinality());
+                if profiles {
+                    print!(" {}", OscillationProfile::new(&graph, scc));
+                }
+            }
+        }
+        println!();
+    }
+
+    println!("all_scc, trivial_scc");
+    println!("{total_scc}, {total_trivial}");
+}
@@ -0,0 +1,39 @@
+use biodivine_lib_algo_scc::basins::fixed_points;
+use biodivine_lib_algo_scc::chain::Config;
+use biodivine_lib_algo_scc::chain::chain;
+use biodivine_lib_algo_scc::dot::condensation_to_dot;
+use biodivine_lib_algo_scc::dot::scc_to_dot;
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+/// The largest SCC that is exported state by state.
+const MAX_STATES: usize = 256;
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    assert!(args.len() == 2 || args.len() == 3);
+
+    let bn = BooleanNetwork::try_from_file(&args[1]).unwrap();
+    let bn = bn.inline_constants(true, true);
+    let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+
+    let mut scc_list = chain(graph.clone(), Config::default()).collect::<Vec<_>>();
+    scc_list.sort_by_key(|it| it.exact_cardinality());
+
+    // the decomposition omits trivial SCCs, but the fixed points are needed as the sinks
+    let mut fixed_points = fixed_points(&graph);
+    while !fixed_points.is_empty() {
+        let fixed_point = fixed_points.pick_singleton();
+        fixed_points = fixed_points.minus(&fixed_point);
+        scc_list.push(fixed_point);
+    }
+
+    match args.get(2) {
+        None => print!("{}", condensation_to_dot(&graph, &scc_list)),
+        Some(index) => {
+            let scc = &scc_list[index.parse::<usize>().unwrap()];
+            print!("{}", scc_to_dot(&graph, scc, MAX_STATES).unwrap());
+        }
+    }
+}
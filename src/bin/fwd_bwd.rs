@@ -1,9 +1,11 @@
+use biodivine_lib_algo_scc::analysis::OscillationProfile;
 use biodivine_lib_param_bn::BooleanNetwork;
 use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
 
 fn main() {
     let args = std::env::args().collect::<Vec<_>>();
-    assert_eq!(args.len(), 2);
+    assert!(args.len() == 2 || (args.len() == 3 && args[2] == "--profiles"));
+    let profiles = args.len() == 3;
 
     let bn = BooleanNetwork::try_from_file(&args[1]).unwrap();
     let bn = bn.inline_constants(true, true);
@@ -12,7 +14,8 @@ fn main() {
     println!("Loaded BN with {} variables.", bn.num_vars());
 
     let mut scc_list =
-        biodivine_lib_algo_scc::fwd_bwd::fwd_bwd_scc_decomposition_naive(graph).collect::<Vec<_>>();
+        biodivine_lib_algo_scc::fwd_bwd::fwd_bwd_scc_decomposition_naive(graph.clone())
+            .collect::<Vec<_>>();
     scc_list.sort_by_key(|it| it.exact_cardinality());
 
     let trivial = scc_list.iter().filter(|it| it.is_singleton()).count();
@@ -22,6 +25,9 @@ fn main() {
     for scc in scc_list.iter().rev().take(100) {
         if !scc.is_singleton() {
             print!(", {}", scc.exact_cardinality());
+            if profiles {
+                print!(" {}", OscillationProfile::new(&graph, scc));
+            }
         }
     }
     println!();
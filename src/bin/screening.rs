@@ -0,0 +1,46 @@
+use biodivine_lib_algo_scc::chain::Config;
+use biodivine_lib_algo_scc::perturbation::Screening;
+use biodivine_lib_algo_scc::perturbation::double_perturbations;
+use biodivine_lib_algo_scc::perturbation::single_perturbations;
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    assert!(args.len() == 2 || (args.len() == 3 && args[2] == "--double"));
+
+    let bn = BooleanNetwork::try_from_file(&args[1]).unwrap();
+    let bn = bn.inline_constants(true, true);
+    let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+
+    println!("Loaded BN with {} variables.", bn.num_vars());
+
+    let variables = bn.variables().collect::<Vec<_>>();
+    let mut perturbations = single_perturbations(&variables);
+    if args.len() == 3 {
+        perturbations.extend(double_perturbations(&variables));
+    }
+
+    let mut screening = Screening::new(graph.clone(), Config::default());
+    println!("Original network: {} SCCs.", screening.original().len());
+
+    println!("perturbation, lost_scc, created_scc");
+    let changed = screening.screen(&perturbations);
+    for (perturbation, comparison) in &changed {
+        let perturbation = perturbation
+            .iter()
+            .map(|(var, value)| format!("{}={}", graph.get_variable_name(*var), u8::from(*value)))
+            .collect::<Vec<_>>();
+        println!(
+            "{}, {}, {}",
+            perturbation.join(" "),
+            comparison.lost.len(),
+            comparison.created.len()
+        );
+    }
+    println!(
+        "{} of {} perturbations change the SCCs.",
+        changed.len(),
+        perturbations.len()
+    );
+}
@@ -1,52 +1,117 @@
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Config {
     pub trim_lvl: TrimLvl,
     pub strategy: Strategy,
+    pub preprocessing: Preprocessing,
+    /// Detect the invariant variables (see [crate::inputs::invariant_variables]) and
+    /// decompose each of the disconnected subspaces given by their valuations separately.
+    pub split_invariant: bool,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub enum Strategy {
     #[default]
     Chain,
     Saturation,
     SaturationHamming,
+    /// Same as [Strategy::SaturationHamming], but the Hamming distance is weighted; there
+    /// must be one weight per network variable (indexed by `VariableId`).
+    /// See also [crate::hamming::out_degree_weights].
+    SaturationWeightedHamming(Arc<[usize]>),
 }
 
-type DecompositionFn = fn(
-    SymbolicAsyncGraph,
-    GraphColoredVertices,
-    fn(&SymbolicAsyncGraph, GraphColoredVertices) -> GraphColoredVertices,
-) -> Vec<GraphColoredVertices>;
-
 impl Strategy {
-    const fn associated_decomposition_fn(&self) -> DecompositionFn {
+    /// Decomposes the subsystem of the `graph` induced by the `restriction`.
+    fn decompose<G: SymbolicTransitionSystem>(
+        &self,
+        graph: &G,
+        restriction: GraphColoredVertices,
+        trim_lvl: TrimLvl,
+    ) -> Vec<GraphColoredVertices> {
         match self {
-            Self::Chain => chain_iterative,
-            Self::Saturation => _chain_saturation,
-            Self::SaturationHamming => _chain_saturation_hamming_heuristic,
+            Self::Chain => trim_lvl.start_decomposition(graph, restriction, chain_iterative),
+            Self::Saturation => trim_lvl.start_decomposition(graph, restriction, _chain_saturation),
+            Self::SaturationHamming => trim_lvl.start_decomposition(
+                graph,
+                restriction,
+                _chain_saturation_hamming_heuristic,
+            ),
+            Self::SaturationWeightedHamming(weights) => trim_lvl.start_decomposition(
+                graph,
+                restriction,
+                |graph, restriction, vertices_hint, restrictor| {
+                    _chain_saturation_weighted_hamming_heuristic(
+                        graph,
+                        restriction,
+                        vertices_hint,
+                        restrictor,
+                        weights,
+                    )
+                },
+            ),
         }
     }
 }
 
-pub fn chain(
-    graph: SymbolicAsyncGraph,
+pub fn chain<G: SymbolicTransitionSystem>(
+    graph: G,
     config: Config,
 ) -> impl Iterator<Item = GraphColoredVertices> {
-    config
-        .trim_lvl
-        .start_decomposition(graph, config.strategy.associated_decomposition_fn())
+    // with `split_invariant`, the subspaces are decomposed lazily, one per valuation of
+    // the invariant variables; otherwise, the states are decomposed at once
+    let (per_input, sccs) = if config.split_invariant {
+        let invariant = invariant_variables(&graph);
+        let config = Config {
+            split_invariant: false,
+            ..config
+        };
+        (Some(chain_per_input(graph, &invariant, config)), Vec::new())
+    } else {
+        (None, decompose_parts(&graph, &config))
+    };
+
+    per_input
+        .into_iter()
+        .flatten()
+        .flat_map(|(_, sccs)| sccs)
+        .chain(sccs)
+}
+
+/// Decomposes the states of the `graph` (preprocessed according to `config`) at once.
+fn decompose_parts<G: SymbolicTransitionSystem>(
+    graph: &G,
+    config: &Config,
+) -> Vec<GraphColoredVertices> {
+    let parts = match config.preprocessing {
+        Preprocessing::None => {
+            let unit = graph.unit_colored_vertices().clone();
+            return config.strategy.decompose(graph, unit, config.trim_lvl);
+        }
+        Preprocessing::Partition => partition(graph),
+        Preprocessing::MinimalTrapSpaces => attractor_parts(graph),
+    };
+
+    parts
         .into_iter()
+        .flat_map(|part| config.strategy.decompose(graph, part, config.trim_lvl))
+        .collect()
 }
 
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
 use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
-use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use std::sync::Arc;
 
 use crate::assert_precondition_graph_not_colored;
 use crate::hamming::Hamming;
+use crate::hints;
+use crate::inputs::chain_per_input;
+use crate::inputs::invariant_variables;
+use crate::preprocessing::attractor_parts;
+use crate::preprocessing::partition;
+use crate::transition_system::SymbolicTransitionSystem;
 use crate::trimming::trim;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum TrimLvl {
     /// Do not trim *trivial SCCs* at all
     #[default]
@@ -61,56 +126,74 @@ pub enum TrimLvl {
     Full,
 }
 
+/// Preprocessing of the state space before the decomposition (see [crate::preprocessing]);
+/// independent of the [TrimLvl].
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Preprocessing {
+    /// Decompose the whole state space at once.
+    #[default]
+    None,
+    /// Partition the state space by the percolated trap set and by the minimal trap
+    /// spaces and decompose every part separately. Yields the same SCCs as
+    /// [Preprocessing::None].
+    Partition,
+    /// Only decompose the minimal trap spaces within the percolated trap set (or the whole
+    /// percolated trap set if the network is not known or the state space is restricted,
+    /// see [crate::preprocessing::minimal_trap_spaces]). Intended for attractor search:
+    /// the SCCs outside of these parts are not reported, but every attractor that lies
+    /// in a minimal trap space is.
+    MinimalTrapSpaces,
+}
+
 impl TrimLvl {
-    fn start_decomposition(
+    /// The states of the `restriction` where the decomposition starts; trimmed unless
+    /// [TrimLvl::None].
+    pub(crate) fn initial_states<G: SymbolicTransitionSystem>(
         &self,
-        graph: SymbolicAsyncGraph,
-        decomposition_fn: impl Fn(
-            SymbolicAsyncGraph,
-            GraphColoredVertices,
-            fn(&SymbolicAsyncGraph, GraphColoredVertices) -> GraphColoredVertices,
-        ) -> Vec<GraphColoredVertices>,
-    ) -> Vec<GraphColoredVertices> {
-        assert_precondition_graph_not_colored(&graph);
+        graph: &G,
+        restriction: GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        match self {
+            TrimLvl::None => restriction,
+            TrimLvl::StartOnly | TrimLvl::Full => trim(graph, restriction),
+        }
+    }
 
-        const fn identity(
-            _: &SymbolicAsyncGraph,
-            it: GraphColoredVertices,
-        ) -> GraphColoredVertices {
+    /// The `restrictor` of the subgraphs in every iteration (see [chain_step]).
+    pub(crate) fn restrictor<G: SymbolicTransitionSystem>(
+        &self,
+    ) -> fn(&G, GraphColoredVertices) -> GraphColoredVertices {
+        const fn identity<G>(_: &G, it: GraphColoredVertices) -> GraphColoredVertices {
             it
         }
 
         match self {
-            TrimLvl::None => match graph.unit_vertices().is_empty() {
-                true => Default::default(),
-                false => {
-                    let no_hint = graph.empty_colored_vertices().clone();
-                    decomposition_fn(graph, no_hint, /* noop - no trim */ identity)
-                }
-            },
-            TrimLvl::StartOnly => {
-                let trimmed = trim(&graph, graph.unit_colored_vertices().clone());
-                let graph = graph.restrict(&trimmed);
-
-                match graph.unit_vertices().is_empty() {
-                    true => Default::default(),
-                    false => {
-                        let no_hint = graph.empty_colored_vertices().clone();
-                        decomposition_fn(graph, no_hint, /* noop - no trim */ identity)
-                    }
-                }
-            }
-            TrimLvl::Full => {
-                let trimmed = trim(&graph, graph.unit_colored_vertices().clone());
-                let graph = graph.restrict(&trimmed);
-
-                match graph.unit_vertices().is_empty() {
-                    true => Default::default(),
-                    false => {
-                        let no_hint = graph.empty_colored_vertices().clone();
-                        decomposition_fn(graph, no_hint, trim)
-                    }
-                }
+            TrimLvl::None | TrimLvl::StartOnly => identity,
+            TrimLvl::Full => trim,
+        }
+    }
+
+    /// Runs the `decomposition_fn` on the (trimmed) subsystem of the `graph` induced by
+    /// the `restriction`, unless it is empty.
+    fn start_decomposition<G: SymbolicTransitionSystem>(
+        &self,
+        graph: &G,
+        restriction: GraphColoredVertices,
+        decomposition_fn: impl Fn(
+            &G,
+            GraphColoredVertices,
+            GraphColoredVertices,
+            fn(&G, GraphColoredVertices) -> GraphColoredVertices,
+        ) -> Vec<GraphColoredVertices>,
+    ) -> Vec<GraphColoredVertices> {
+        assert_precondition_graph_not_colored(graph);
+
+        let restriction = self.initial_states(graph, restriction);
+        match restriction.is_empty() {
+            true => Default::default(),
+            false => {
+                let no_hint = graph.empty_colored_vertices().clone();
+                decomposition_fn(graph, restriction, no_hint, self.restrictor())
             }
         }
     }
@@ -120,147 +203,187 @@ impl TrimLvl {
 ///
 ///  expects all the args to be of the same graph (given by the first parameter)
 ///
-/// expects a nonempty graph (must be able to pick a pivot)
+/// expects a nonempty restriction (must be able to pick a pivot)
 ///
 /// only works on a graph with a single color (aka no colors)
 ///
 /// # Arguments
 ///
-/// * `graph` - the graph to be decomposed - must not be empty
+/// * `graph` - the (base) graph; only its subsystem induced by `restriction` is decomposed
+/// * `restriction` - the states to be decomposed - must not be empty
 /// * `vertices_hint` - the vertices that are already in the scc
 /// * `restrictor` - function that further restricts the sets that are to be
 ///   "recursively" decomposed into SCCs. Pass in `|_, it| it` to ignore this.
-fn chain_iterative(
-    graph: SymbolicAsyncGraph,
+fn chain_iterative<G: SymbolicTransitionSystem>(
+    graph: &G,
+    restriction: GraphColoredVertices,
     vertices_hint: GraphColoredVertices,
-    restrictor: fn(&SymbolicAsyncGraph, GraphColoredVertices) -> GraphColoredVertices,
+    restrictor: fn(&G, GraphColoredVertices) -> GraphColoredVertices,
 ) -> Vec<GraphColoredVertices> {
     let mut output = Vec::<GraphColoredVertices>::new();
-    let mut stack = vec![(graph, vertices_hint)];
-
-    while let Some((graph, vertices_hint)) = stack.pop() {
-        assert!(!graph.unit_vertices().is_empty());
-        assert!(vertices_hint.is_subset(graph.unit_colored_vertices()));
-
-        let pivot_set = match vertices_hint.is_empty() {
-            true => graph.unit_colored_vertices(),
-            false => &vertices_hint,
-        };
-        let pivot = pivot_set.pick_singleton();
+    // only the restrictions are stored; the restricted graphs are never built
+    let mut stack = vec![(restriction, vertices_hint)];
 
-        assert!(!pivot.is_empty()); // trivially true; subgraph is nonempty (else returned above)
+    while let Some((restriction, vertices_hint)) = stack.pop() {
+        let the_scc = chain_step(graph, &restriction, vertices_hint, restrictor, &mut stack);
 
-        let mut fwd_reachable_acc = pivot.clone();
-        let mut current_layer = pivot.clone();
-        loop {
-            let next_layer = graph.post(&current_layer).minus(&fwd_reachable_acc); // take only the *proper* layer
+        // Output the scc.
+        if !the_scc.is_singleton() {
+            // todo this filter should probably be a part of a config/parameter
+            // - or use trimming
+            output.push(the_scc);
+        }
+    }
 
-            if next_layer.is_empty() {
-                break;
-            }
+    output
+}
 
-            fwd_reachable_acc = fwd_reachable_acc.union(&next_layer);
-            current_layer = next_layer;
+/// A single iteration of [chain_iterative]: finds the SCC of a pivot (picked from
+/// the `vertices_hint` if possible) within the `restriction` of the `graph`, and pushes
+/// the (at most two) remaining restrictions to be decomposed, together with their hints,
+/// onto the `stack`.
+///
+/// Returns the SCC of the pivot (which may be trivial).
+pub(crate) fn chain_step<G: SymbolicTransitionSystem>(
+    graph: &G,
+    restriction: &GraphColoredVertices,
+    vertices_hint: GraphColoredVertices,
+    restrictor: fn(&G, GraphColoredVertices) -> GraphColoredVertices,
+    stack: &mut Vec<(GraphColoredVertices, GraphColoredVertices)>,
+) -> GraphColoredVertices {
+    assert!(!restriction.is_empty());
+    assert!(vertices_hint.is_subset(restriction));
+
+    let pivot_set = match vertices_hint.is_empty() {
+        true => restriction,
+        false => &vertices_hint,
+    };
+    let pivot = pivot_set.pick_singleton();
+
+    assert!(!pivot.is_empty()); // trivially true; subgraph is nonempty (else returned above)
+
+    let mut fwd_reachable_acc = pivot.clone();
+    let mut current_layer = pivot.clone();
+    loop {
+        let next_layer = graph
+            .post_within(&current_layer, restriction)
+            .minus(&fwd_reachable_acc); // take only the *proper* layer
+
+        if next_layer.is_empty() {
+            break;
         }
 
-        let fwd_reachable = fwd_reachable_acc;
-        let last_fwd_layer = current_layer;
+        fwd_reachable_acc = fwd_reachable_acc.union(&next_layer);
+        current_layer = next_layer;
+    }
 
-        let mut restricted_bwd_reachable_acc = pivot;
-        let graph_fwd_restricted = graph.restrict(&fwd_reachable);
-        loop {
-            let restricted_pre = graph_fwd_restricted // not really a proper *layer*; not cleaned (`.minus(...)`)
-                .pre(&restricted_bwd_reachable_acc);
+    let fwd_reachable = fwd_reachable_acc;
+    let last_fwd_layer = current_layer;
 
-            if restricted_pre.is_subset(&restricted_bwd_reachable_acc) {
-                break; // no further progress possible
-            }
+    let mut restricted_bwd_reachable_acc = pivot;
+    loop {
+        let restricted_pre = graph // not really a proper *layer*; not cleaned (`.minus(...)`)
+            .pre_within(&restricted_bwd_reachable_acc, &fwd_reachable);
 
-            restricted_bwd_reachable_acc = restricted_bwd_reachable_acc.union(&restricted_pre);
+        if restricted_pre.is_subset(&restricted_bwd_reachable_acc) {
+            break; // no further progress possible
         }
 
-        let the_scc = restricted_bwd_reachable_acc;
-
-        let fwd_remaining = fwd_reachable.minus(&the_scc);
-        let fwd_remaining = restrictor(&graph, fwd_remaining);
-        if !fwd_remaining.is_empty() {
-            let fwd_subgraph = graph.restrict(&fwd_remaining);
-
-            // must intersect with `fwd_remaining`; it might have changed
-            // -> no need to `minus scc`; `fwd_remaining` does not contain scc
+        restricted_bwd_reachable_acc = restricted_bwd_reachable_acc.union(&restricted_pre);
+    }
 
-            // todo `last_layer` and `fwd_remaining` may have diverged
-            //  (`restrictor` fn might have "cut off" the whole
-            //  `last_fwd_layer` part, their intersection might now be empty;
-            //  the alg still can work with *empty hint*, the perf might suffer
-            //  tho)
-            let fwd_hint = last_fwd_layer.intersect(&fwd_remaining);
+    let the_scc = restricted_bwd_reachable_acc;
 
-            // "recursive call"
-            stack.push((fwd_subgraph, fwd_hint));
-        }
-
-        let rest_remaining = graph.unit_colored_vertices().minus(&fwd_reachable);
-        let rest_remaining = restrictor(&graph, rest_remaining);
-        if !rest_remaining.is_empty() {
-            let rest_subgraph = graph.restrict(&rest_remaining);
+    let fwd_outside_scc = fwd_reachable.minus(&the_scc);
+    let fwd_remaining = restrictor(graph, fwd_outside_scc.clone());
+    if !fwd_remaining.is_empty() {
+        // the restrictor might have cut off the whole `last_fwd_layer`
+        let fwd_hint = hints::fwd_hint(graph, &last_fwd_layer, &fwd_outside_scc, &fwd_remaining);
 
-            // todo same as in the other branch; hint might be empty (even in
-            // cases `rest_subgraph` is nonempty), in cases restrictor trimmed
-            // too much
-            let rest_hint = rest_subgraph.pre(&the_scc);
+        // "recursive call"
+        stack.push((fwd_remaining, fwd_hint));
+    }
 
-            // "recursive call"
-            stack.push((rest_subgraph, rest_hint));
-        }
+    let rest_remaining = restriction.minus(&fwd_reachable);
+    let rest_remaining = restrictor(graph, rest_remaining);
+    if !rest_remaining.is_empty() {
+        let rest_hint = hints::rest_hint(graph, &the_scc, restriction, &rest_remaining);
 
-        // Output the scc.
-        if !the_scc.is_singleton() {
-            // todo this filter should probably be a part of a config/parameter
-            // - or use trimming
-            output.push(the_scc);
-        }
+        // "recursive call"
+        stack.push((rest_remaining, rest_hint));
     }
 
-    output
+    the_scc
+}
+
+pub(crate) fn fwd_saturation<G: SymbolicTransitionSystem>(
+    graph: &G,
+    initial: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    fwd_saturation_within(graph, initial, graph.unit_colored_vertices())
 }
 
-fn fwd_saturation(
-    graph: &SymbolicAsyncGraph,
+/// [fwd_saturation] within the subsystem of the `graph` induced by the `set`.
+pub(crate) fn fwd_saturation_within<G: SymbolicTransitionSystem>(
+    graph: &G,
     initial: &GraphColoredVertices,
+    set: &GraphColoredVertices,
 ) -> GraphColoredVertices {
+    fwd_saturation_with_frontier(graph, initial, set).0
+}
+
+/// [fwd_saturation_within], also returning the *frontier*: the states added by the last
+/// (nonempty) saturation step, or the `initial` states if there was none. The saturation
+/// has no layers; the frontier serves as the last layer when picking the next pivot (see
+/// [crate::hints]).
+fn fwd_saturation_with_frontier<G: SymbolicTransitionSystem>(
+    graph: &G,
+    initial: &GraphColoredVertices,
+    set: &GraphColoredVertices,
+) -> (GraphColoredVertices, GraphColoredVertices) {
     let mut result_accumulator = initial.clone();
+    let mut frontier = initial.clone();
 
-    // better to collect; there is some computation to producing `variables()`
-    let rev_variables = graph.variables().rev().collect::<Vec<_>>();
+    let mut rev_variables = graph.variables();
+    rev_variables.reverse();
 
     'from_bottom_var: loop {
         for var in rev_variables.iter() {
-            let step = graph.var_post_out(*var, &result_accumulator);
+            let step = graph.var_post_out_within(*var, &result_accumulator, set);
 
             if !step.is_empty() {
                 result_accumulator = result_accumulator.union(&step);
+                frontier = step;
 
                 continue 'from_bottom_var;
             }
         }
 
-        break result_accumulator;
+        break (result_accumulator, frontier);
     }
 }
 
-fn bwd_saturation(
-    graph: &SymbolicAsyncGraph,
+pub(crate) fn bwd_saturation<G: SymbolicTransitionSystem>(
+    graph: &G,
+    initial: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    bwd_saturation_within(graph, initial, graph.unit_colored_vertices())
+}
+
+/// [bwd_saturation] within the subsystem of the `graph` induced by the `set`.
+pub(crate) fn bwd_saturation_within<G: SymbolicTransitionSystem>(
+    graph: &G,
     initial: &GraphColoredVertices,
+    set: &GraphColoredVertices,
 ) -> GraphColoredVertices {
     let mut result_accumulator = initial.clone();
 
-    // better to collect; there is some computation to producing `variables()`
-    let rev_variables = graph.variables().rev().collect::<Vec<_>>();
+    let mut rev_variables = graph.variables();
+    rev_variables.reverse();
 
     'from_bottom_var: loop {
         for var in rev_variables.iter() {
-            let step = graph.var_pre_out(*var, &result_accumulator);
+            let step = graph.var_pre_out_within(*var, &result_accumulator, set);
 
             if !step.is_empty() {
                 result_accumulator = result_accumulator.union(&step);
@@ -273,48 +396,46 @@ fn bwd_saturation(
     }
 }
 
-fn _chain_saturation(
-    graph: SymbolicAsyncGraph,
+fn _chain_saturation<G: SymbolicTransitionSystem>(
+    graph: &G,
+    restriction: GraphColoredVertices,
     vertices_hint: GraphColoredVertices,
-    restrictor: fn(&SymbolicAsyncGraph, GraphColoredVertices) -> GraphColoredVertices,
+    restrictor: fn(&G, GraphColoredVertices) -> GraphColoredVertices,
 ) -> Vec<GraphColoredVertices> {
     let mut output = Vec::<GraphColoredVertices>::new();
-    let mut stack = vec![(graph, vertices_hint)];
+    let mut stack = vec![(restriction, vertices_hint)];
 
-    while let Some((graph, vertices_hint)) = stack.pop() {
-        assert!(!graph.unit_vertices().is_empty());
+    while let Some((restriction, vertices_hint)) = stack.pop() {
+        assert!(!restriction.is_empty());
 
         let pivot_set = match vertices_hint.is_empty() {
-            true => graph.unit_colored_vertices(),
+            true => &restriction,
             false => &vertices_hint,
         };
         let pivot = pivot_set.pick_singleton();
 
         assert!(!pivot.is_empty()); // trivially true; subgraph is nonempty (else returned above)
 
-        let fwd_reachable = fwd_saturation(&graph, &pivot);
+        let (fwd_reachable, frontier) = fwd_saturation_with_frontier(graph, &pivot, &restriction);
 
-        let scc = bwd_saturation(&graph.restrict(&fwd_reachable), &pivot);
+        let scc = bwd_saturation_within(graph, &pivot, &fwd_reachable);
 
-        let fwd_remaining = fwd_reachable.minus(&scc);
-        let fwd_remaining = restrictor(&graph, fwd_remaining);
+        let fwd_outside_scc = fwd_reachable.minus(&scc);
+        let fwd_remaining = restrictor(graph, fwd_outside_scc.clone());
         if !fwd_remaining.is_empty() {
-            let fwd_subgraph = graph.restrict(&fwd_remaining);
-            // no better estimate in this implementation
-            let fwd_hint = fwd_remaining;
+            let fwd_hint = hints::fwd_hint(graph, &frontier, &fwd_outside_scc, &fwd_remaining);
 
             // chain_rec_saturation(&fwd_subgraph, &fwd_hint, scc_dump);
-            stack.push((fwd_subgraph, fwd_hint));
+            stack.push((fwd_remaining, fwd_hint));
         }
 
-        let rest_remaining = graph.unit_colored_vertices().minus(&fwd_reachable);
-        let rest_remaining = restrictor(&graph, rest_remaining);
+        let rest_remaining = restriction.minus(&fwd_reachable);
+        let rest_remaining = restrictor(graph, rest_remaining);
         if !rest_remaining.is_empty() {
-            let rest_subgraph = graph.restrict(&rest_remaining);
-            let rest_hint = graph.pre(&scc).intersect(&rest_remaining);
+            let rest_hint = hints::rest_hint(graph, &scc, &restriction, &rest_remaining);
 
             // chain_rec_saturation(&rest_subgraph, &rest_hint, scc_dump);
-            stack.push((rest_subgraph, rest_hint));
+            stack.push((rest_remaining, rest_hint));
         }
 
         // Output the scc.
@@ -327,50 +448,83 @@ fn _chain_saturation(
     output
 }
 
-fn _chain_saturation_hamming_heuristic(
-    graph: SymbolicAsyncGraph,
+fn _chain_saturation_hamming_heuristic<G: SymbolicTransitionSystem>(
+    graph: &G,
+    restriction: GraphColoredVertices,
+    vertices_hint: GraphColoredVertices,
+    restrictor: fn(&G, GraphColoredVertices) -> GraphColoredVertices,
+) -> Vec<GraphColoredVertices> {
+    chain_saturation_hamming(
+        graph,
+        restriction,
+        vertices_hint,
+        restrictor,
+        |_, pivot, choice_set| pivot.ham_furthest_within(choice_set),
+    )
+}
+
+fn _chain_saturation_weighted_hamming_heuristic<G: SymbolicTransitionSystem>(
+    graph: &G,
+    restriction: GraphColoredVertices,
     vertices_hint: GraphColoredVertices,
-    restrictor: fn(&SymbolicAsyncGraph, GraphColoredVertices) -> GraphColoredVertices,
+    restrictor: fn(&G, GraphColoredVertices) -> GraphColoredVertices,
+    weights: &[usize],
 ) -> Vec<GraphColoredVertices> {
-    let graph = graph.clone();
-    let vertices_hint = vertices_hint.clone();
+    chain_saturation_hamming(
+        graph,
+        restriction,
+        vertices_hint,
+        restrictor,
+        |graph, pivot, choice_set| pivot.ham_furthest_within_weighted(graph, choice_set, weights),
+    )
+}
 
-    let mut stack = vec![(graph, vertices_hint)];
+/// the saturation decomposition, picking the next pivot by the given `furthest_within`
+/// function (of the graph, the current pivot and the set to choose from)
+fn chain_saturation_hamming<G, F>(
+    graph: &G,
+    restriction: GraphColoredVertices,
+    vertices_hint: GraphColoredVertices,
+    restrictor: fn(&G, GraphColoredVertices) -> GraphColoredVertices,
+    furthest_within: F,
+) -> Vec<GraphColoredVertices>
+where
+    G: SymbolicTransitionSystem,
+    F: Fn(&G, &GraphColoredVertices, &GraphColoredVertices) -> GraphColoredVertices,
+{
+    let mut stack = vec![(restriction, vertices_hint)];
     let mut ouput = Vec::<GraphColoredVertices>::new();
 
-    while let Some((graph, vertices_hint)) = stack.pop() {
-        assert!(!graph.unit_vertices().is_empty());
+    while let Some((restriction, vertices_hint)) = stack.pop() {
+        assert!(!restriction.is_empty());
 
         let pivot_set = match vertices_hint.is_empty() {
-            true => graph.unit_colored_vertices(),
+            true => &restriction,
             false => &vertices_hint,
         };
         let pivot = pivot_set.pick_singleton();
 
         assert!(!pivot.is_empty()); // trivially true; subgraph is nonempty (else returned above)
 
-        let fwd_reachable = fwd_saturation(&graph, &pivot);
+        let fwd_reachable = fwd_saturation_within(graph, &pivot, &restriction);
 
-        let scc = bwd_saturation(&graph.restrict(&fwd_reachable), &pivot);
+        let scc = bwd_saturation_within(graph, &pivot, &fwd_reachable);
 
         let fwd_remaining = fwd_reachable.minus(&scc);
-        let fwd_remaining = restrictor(&graph, fwd_remaining);
+        let fwd_remaining = restrictor(graph, fwd_remaining);
         if !fwd_remaining.is_empty() {
-            let fwd_subgraph = graph.restrict(&fwd_remaining);
-
-            let fwd_hint = pivot.ham_furthest_within(&fwd_remaining); // <-- the difference
+            let fwd_hint = furthest_within(graph, &pivot, &fwd_remaining); // <-- the difference
 
             // chain_rec_saturation_hamming_heuristic(&fwd_subgraph, &fwd_hint, scc_dump);
-            stack.push((fwd_subgraph, fwd_hint));
+            stack.push((fwd_remaining, fwd_hint));
         }
 
-        let rest_remaining = graph.unit_colored_vertices().minus(&fwd_reachable);
-        let rest_remaining = restrictor(&graph, rest_remaining);
+        let rest_remaining = restriction.minus(&fwd_reachable);
+        let rest_remaining = restrictor(graph, rest_remaining);
         if !rest_remaining.is_empty() {
-            let rest_subgraph = graph.restrict(&rest_remaining);
-            let rest_hint = graph.pre(&scc).intersect(&rest_remaining);
+            let rest_hint = hints::rest_hint(graph, &scc, &restriction, &rest_remaining);
             // chain_rec_saturation_hamming_heuristic(&rest_subgraph, &rest_hint, scc_dump);
-            stack.push((rest_subgraph, rest_hint));
+            stack.push((rest_remaining, rest_hint));
         }
 
         // Output the scc.
@@ -386,12 +540,19 @@ fn _chain_saturation_hamming_heuristic(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::explicit::ExplicitAsyncGraph;
     use crate::fwd_bwd::fwd_bwd_scc_decomposition_naive;
+    use crate::hamming::out_degree_weights;
+    use crate::test_utils::arb_network;
+    use crate::test_utils::skip_large_model;
+    use crate::test_utils::skip_threshold;
     use biodivine_lib_param_bn::BooleanNetwork;
     use biodivine_lib_param_bn::biodivine_std::traits::Set;
     use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
     use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
     use num_bigint::BigUint;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
     use std::collections::HashSet;
     use test_generator::test_resources;
 
@@ -473,6 +634,7 @@ mod tests {
                 Config {
                     strategy: Strategy::Chain,
                     trim_lvl: TrimLvl::None,
+                    ..Default::default()
                 },
             )
         });
@@ -488,6 +650,7 @@ mod tests {
                 Config {
                     strategy: Strategy::Saturation,
                     trim_lvl: TrimLvl::None,
+                    ..Default::default()
                 },
             )
         });
@@ -501,6 +664,21 @@ mod tests {
                 Config {
                     strategy: Strategy::SaturationHamming,
                     trim_lvl: TrimLvl::None,
+                    ..Default::default()
+                },
+            )
+        });
+    }
+
+    #[test]
+    fn chain_saturation_weighted_hamming_heuristic_test() {
+        basic_decomposition(|graph| {
+            chain(
+                graph,
+                Config {
+                    strategy: Strategy::SaturationWeightedHamming(vec![1, 2].into()),
+                    trim_lvl: TrimLvl::None,
+                    ..Default::default()
                 },
             )
         });
@@ -515,6 +693,7 @@ mod tests {
             Config {
                 strategy: Strategy::Chain,
                 trim_lvl: TrimLvl::None,
+                ..Default::default()
             },
         )
         .collect::<HashSet<_>>();
@@ -531,20 +710,7 @@ mod tests {
         let bn = BooleanNetwork::try_from_file(model_path).unwrap();
         let bn = bn.inline_constants(true, true);
 
-        let skip_threshold = if cfg!(feature = "expensive-tests") {
-            14
-        } else {
-            10
-        };
-
-        if bn.num_vars() > skip_threshold {
-            // The network is too large.
-            println!(
-                " >> [{} > {}] Skipping {}.",
-                bn.num_vars(),
-                skip_threshold,
-                model_path
-            );
+        if skip_large_model(&bn, model_path) {
             return;
         }
 
@@ -571,20 +737,7 @@ mod tests {
         let bn = BooleanNetwork::try_from_file(model_path).unwrap();
         let bn = bn.inline_constants(true, true);
 
-        let skip_threshold = if cfg!(feature = "expensive-tests") {
-            14
-        } else {
-            10
-        };
-
-        if bn.num_vars() > skip_threshold {
-            // The network is too large.
-            println!(
-                " >> [{} > {}] Skipping {}.",
-                bn.num_vars(),
-                skip_threshold,
-                model_path
-            );
+        if skip_large_model(&bn, model_path) {
             return;
         }
 
@@ -597,7 +750,7 @@ mod tests {
         println!(
             " >> [{} <= {}] Testing {}.",
             bn.num_vars(),
-            skip_threshold,
+            skip_threshold(),
             model_path
         );
 
@@ -621,6 +774,7 @@ mod tests {
                 Config {
                     trim_lvl: TrimLvl::None,
                     strategy: Strategy::Chain,
+                    ..Default::default()
                 },
             )
         });
@@ -634,6 +788,7 @@ mod tests {
                 Config {
                     trim_lvl: TrimLvl::None,
                     strategy: Strategy::Saturation,
+                    ..Default::default()
                 },
             )
         });
@@ -647,6 +802,22 @@ mod tests {
                 Config {
                     strategy: Strategy::SaturationHamming,
                     trim_lvl: TrimLvl::None,
+                    ..Default::default()
+                },
+            )
+        });
+    }
+
+    #[test_resources("./models/bbm-inputs-true/*.aeon")]
+    fn compare_chain_saturation_weighted_hamming_heuristic_fwd_bwd_selected(model_path: &str) {
+        compare_fn_with_fwd_bwd(model_path, |graph| {
+            let weights = out_degree_weights(graph.as_network().unwrap().as_graph());
+            chain(
+                graph,
+                Config {
+                    strategy: Strategy::SaturationWeightedHamming(weights.into()),
+                    trim_lvl: TrimLvl::None,
+                    ..Default::default()
                 },
             )
         });
@@ -660,6 +831,7 @@ mod tests {
                 Config {
                     trim_lvl,
                     strategy: Strategy::Chain,
+                    ..Default::default()
                 },
             )
         })
@@ -673,6 +845,7 @@ mod tests {
                 Config {
                     strategy: Strategy::Saturation,
                     trim_lvl,
+                    ..Default::default()
                 },
             )
         });
@@ -686,6 +859,7 @@ mod tests {
                 Config {
                     strategy: Strategy::SaturationHamming,
                     trim_lvl,
+                    ..Default::default()
                 },
             )
         });
@@ -695,20 +869,7 @@ mod tests {
         let bn = BooleanNetwork::try_from_file(model_path).unwrap();
         let bn = bn.inline_constants(true, true);
 
-        let skip_threshold = if cfg!(feature = "expensive-tests") {
-            14
-        } else {
-            10
-        };
-
-        if bn.num_vars() > skip_threshold {
-            // The network is too large.
-            println!(
-                " >> [{} > {}] Skipping {}.",
-                bn.num_vars(),
-                skip_threshold,
-                model_path
-            );
+        if skip_large_model(&bn, model_path) {
             return;
         }
 
@@ -721,7 +882,7 @@ mod tests {
         println!(
             " >> [{} <= {}] Testing {}.",
             bn.num_vars(),
-            skip_threshold,
+            skip_threshold(),
             model_path
         );
 
@@ -730,6 +891,7 @@ mod tests {
             Config {
                 trim_lvl: TrimLvl::None, // todo fix
                 strategy: Strategy::Chain,
+                ..Default::default()
             },
         )
         .collect::<HashSet<_>>();
@@ -738,6 +900,7 @@ mod tests {
             Config {
                 strategy: Strategy::Saturation,
                 trim_lvl,
+                ..Default::default()
             },
         )
         .collect::<HashSet<_>>();
@@ -746,6 +909,7 @@ mod tests {
             Config {
                 strategy: Strategy::SaturationHamming,
                 trim_lvl,
+                ..Default::default()
             },
         )
         .collect::<HashSet<_>>();
@@ -763,4 +927,37 @@ mod tests {
     fn compare_trimming_full(model_path: &str) {
         compare_all_trimmings(model_path, TrimLvl::Full);
     }
+
+    proptest! {
+        #[test]
+        fn compare_all_configs_with_explicit_random(bn in arb_network(8)) {
+            let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+            let explicit = ExplicitAsyncGraph::new(&bn).unwrap();
+
+            let explicit_scc_set = explicit
+                .scc_decomposition()
+                .into_iter()
+                .filter(|scc| scc.len() > 1)
+                .map(|scc| explicit.to_symbolic(&graph, &scc))
+                .collect::<HashSet<_>>();
+
+            for strategy in [
+                Strategy::Chain,
+                Strategy::Saturation,
+                Strategy::SaturationHamming,
+                Strategy::SaturationWeightedHamming(out_degree_weights(bn.as_graph()).into()),
+            ] {
+                for trim_lvl in [TrimLvl::None, TrimLvl::StartOnly, TrimLvl::Full] {
+                    let config = Config {
+                        trim_lvl,
+                        strategy: strategy.clone(),
+                        ..Default::default()
+                    };
+                    let chain_scc_set = chain(graph.clone(), config).collect::<HashSet<_>>();
+
+                    prop_assert_eq!(&chain_scc_set, &explicit_scc_set);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,415 @@
+//! Checkpointing of the chain decomposition, so that long runs can be resumed after being
+//! killed (timeout, out of memory, ...).
+//!
+//! A [Checkpoint] captures the state of the chain decomposition (as used by [crate::chain]
+//! with [crate::chain::Strategy::Chain]): the work stack of `(restriction, hint)` pairs and
+//! the SCCs output so far. Every restriction is the set of states of a subgraph of the
+//! original graph that remains to be decomposed.
+//!
+//! The binary format is a magic header, the number of BDD variables of the symbolic
+//! context, a fingerprint of the graph (a hash of the names of the variables, of their
+//! update functions and of the set of states), the [TrimLvl], and then the length-prefixed
+//! BDDs of the stack and of the output (see `Bdd::write_as_bytes`).
+
+use crate::assert_precondition_graph_not_colored;
+use crate::chain::TrimLvl;
+use crate::chain::chain_step;
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"SCCCHAIN";
+
+/// The state of a (possibly unfinished) chain decomposition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The number of BDD variables of the symbolic context of the decomposed graph.
+    num_vars: u16,
+    /// The [fingerprint] of the decomposed graph.
+    fingerprint: u64,
+    trim_lvl: TrimLvl,
+    /// The `(restriction, hint)` pairs still to be decomposed; the top is the last one.
+    stack: Vec<(GraphColoredVertices, GraphColoredVertices)>,
+    /// The non-trivial SCCs found so far.
+    output: Vec<GraphColoredVertices>,
+}
+
+impl Checkpoint {
+    /// The initial state of the decomposition of the `graph`, trimmed according to
+    /// the `trim_lvl`.
+    pub fn new<G: SymbolicTransitionSystem>(graph: &G, trim_lvl: TrimLvl) -> Self {
+        assert_precondition_graph_not_colored(graph);
+
+        let unit = trim_lvl.initial_states(graph, graph.unit_colored_vertices().clone());
+        let stack = match unit.is_empty() {
+            true => Vec::new(),
+            false => vec![(unit, graph.empty_colored_vertices().clone())],
+        };
+
+        Checkpoint {
+            num_vars: graph.symbolic_context().bdd_variable_set().num_vars(),
+            fingerprint: fingerprint(graph),
+            trim_lvl,
+            stack,
+            output: Vec::new(),
+        }
+    }
+
+    pub fn trim_lvl(&self) -> TrimLvl {
+        self.trim_lvl
+    }
+
+    /// The non-trivial SCCs found so far.
+    pub fn output(&self) -> &[GraphColoredVertices] {
+        &self.output
+    }
+
+    /// The number of subgraphs still to be decomposed.
+    pub fn remaining(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Writes the checkpoint to the file at `path`.
+    ///
+    /// The checkpoint is first written to a temporary file next to `path` (with `.tmp`
+    /// appended to its name), which then replaces `path`; a run killed while saving never
+    /// leaves a corrupted checkpoint.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let name = path
+            .file_name()
+            .ok_or_else(|| format!("Invalid checkpoint path {}.", path.display()))?;
+        let temporary = path.with_file_name(format!("{}.tmp", name.to_string_lossy()));
+        let file = File::create(&temporary).map_err(|e| e.to_string())?;
+        let mut output = BufWriter::new(file);
+        self.write(&mut output).map_err(|e| e.to_string())?;
+        output
+            .into_inner()
+            .map_err(|e| e.to_string())?
+            .sync_all()
+            .map_err(|e| e.to_string())?;
+        std::fs::rename(&temporary, path).map_err(|e| e.to_string())
+    }
+
+    /// Reads a checkpoint of a decomposition of the `graph` from the file at `path`.
+    ///
+    /// Fails if the file is not a checkpoint or if it was created for a different network
+    /// (with different variables or update functions).
+    pub fn load<G: SymbolicTransitionSystem>(graph: &G, path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        Self::read(graph, &mut BufReader::new(file))
+    }
+
+    fn write<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        output.write_all(MAGIC)?;
+        output.write_all(&self.num_vars.to_le_bytes())?;
+        output.write_all(&self.fingerprint.to_le_bytes())?;
+        output.write_all(&[trim_lvl_to_byte(self.trim_lvl)])?;
+
+        output.write_all(&(self.stack.len() as u64).to_le_bytes())?;
+        for (restriction, hint) in &self.stack {
+            write_bdd(output, restriction.as_bdd())?;
+            write_bdd(output, hint.as_bdd())?;
+        }
+        output.write_all(&(self.output.len() as u64).to_le_bytes())?;
+        for scc in &self.output {
+            write_bdd(output, scc.as_bdd())?;
+        }
+        Ok(())
+    }
+
+    fn read<G: SymbolicTransitionSystem, R: Read>(
+        graph: &G,
+        input: &mut R,
+    ) -> Result<Self, String> {
+        let mut magic = [0u8; 8];
+        read_exact(input, &mut magic)?;
+        if &magic != MAGIC {
+            return Err("The file is not a decomposition checkpoint.".to_string());
+        }
+
+        let context = graph.symbolic_context();
+        let num_vars = u16::from_le_bytes(read_array(input)?);
+        if num_vars != context.bdd_variable_set().num_vars() {
+            return Err(format!(
+                "The checkpoint has {num_vars} BDD variables, but the graph has {}.",
+                context.bdd_variable_set().num_vars()
+            ));
+        }
+        let fingerprint = u64::from_le_bytes(read_array(input)?);
+        if fingerprint != self::fingerprint(graph) {
+            return Err("The checkpoint was created for a different network.".to_string());
+        }
+        let [trim_lvl] = read_array(input)?;
+        let trim_lvl = trim_lvl_from_byte(trim_lvl)?;
+
+        let read_set = |input: &mut R| {
+            read_bdd(input, num_vars).map(|bdd| GraphColoredVertices::new(bdd, context))
+        };
+        let stack_len = u64::from_le_bytes(read_array(input)?);
+        let stack = (0..stack_len)
+            .map(|_| Ok((read_set(input)?, read_set(input)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let output_len = u64::from_le_bytes(read_array(input)?);
+        let output = (0..output_len)
+            .map(|_| read_set(input))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if input.read(&mut [0u8]).map_err(|e| e.to_string())? != 0 {
+            return Err("Unexpected data after the end of the checkpoint.".to_string());
+        }
+
+        Ok(Checkpoint {
+            num_vars,
+            fingerprint,
+            trim_lvl,
+            stack,
+            output,
+        })
+    }
+}
+
+/// Continues the decomposition of the `graph` from the `checkpoint` (created for this
+/// `graph`) until it is finished, and returns all the non-trivial SCCs (including the ones
+/// found before the checkpoint).
+///
+/// After every `interval` steps (one step decomposes one subgraph from the stack), and once
+/// the decomposition is finished, `save` is called with the current state of
+/// the decomposition.
+pub fn chain_from_checkpoint<G: SymbolicTransitionSystem>(
+    graph: &G,
+    mut checkpoint: Checkpoint,
+    interval: usize,
+    mut save: impl FnMut(&Checkpoint),
+) -> Vec<GraphColoredVertices> {
+    assert!(interval > 0);
+
+    let restrictor = checkpoint.trim_lvl.restrictor();
+    let mut steps = 0;
+    while let Some((restriction, hint)) = checkpoint.stack.pop() {
+        let the_scc = chain_step(graph, &restriction, hint, restrictor, &mut checkpoint.stack);
+        if !the_scc.is_singleton() {
+            checkpoint.output.push(the_scc);
+        }
+
+        steps += 1;
+        if steps % interval == 0 || checkpoint.is_finished() {
+            save(&checkpoint);
+        }
+    }
+
+    checkpoint.output
+}
+
+/// A fingerprint of the `graph`: a hash of the names of its variables, of their update
+/// functions (if its network is known) and of the set of its states. The states tell apart
+/// the restrictions of the same network (e.g. different perturbations).
+///
+/// Uses FNV-1a, which (unlike the hashers of the standard library) is stable across builds.
+fn fingerprint<G: SymbolicTransitionSystem>(graph: &G) -> u64 {
+    let context = graph.symbolic_context();
+    let mut description = Vec::new();
+    for var in graph.variables() {
+        description.extend(context.get_network_variable_name(var).bytes());
+        description.push(b'\n');
+        if let Some(network) = graph.as_network() {
+            if let Some(function) = network.get_update_function(var) {
+                description.extend(function.to_string(network).bytes());
+            }
+            description.push(b'\n');
+        }
+    }
+    description.extend(graph.unit_colored_vertices().as_bdd().to_bytes());
+
+    description
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+fn trim_lvl_to_byte(trim_lvl: TrimLvl) -> u8 {
+    match trim_lvl {
+        TrimLvl::None => 0,
+        TrimLvl::StartOnly => 1,
+        TrimLvl::Full => 2,
+    }
+}
+
+fn trim_lvl_from_byte(byte: u8) -> Result<TrimLvl, String> {
+    match byte {
+        0 => Ok(TrimLvl::None),
+        1 => Ok(TrimLvl::StartOnly),
+        2 => Ok(TrimLvl::Full),
+        _ => Err(format!("Invalid trim level {byte}.")),
+    }
+}
+
+/// Writes the number of bytes of the `bdd`, followed by the bytes themselves.
+pub(crate) fn write_bdd<W: Write>(output: &mut W, bdd: &Bdd) -> std::io::Result<()> {
+    let bytes = bdd.to_bytes();
+    output.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    output.write_all(&bytes)
+}
+
+/// Reads a BDD written by [write_bdd] and checks it is over `num_vars` variables.
+pub(crate) fn read_bdd<R: Read>(input: &mut R, num_vars: u16) -> Result<Bdd, String> {
+    let len = u64::from_le_bytes(read_array(input)?);
+    // every node takes 10 bytes; there is at least one terminal
+    if len % 10 != 0 || len == 0 {
+        return Err(format!("Invalid BDD length {len}."));
+    }
+
+    let mut bytes = Vec::new();
+    input
+        .take(len)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    if bytes.len() as u64 != len {
+        return Err("The checkpoint is truncated.".to_string());
+    }
+
+    let bdd = Bdd::from_bytes(&mut bytes.as_slice());
+    match bdd.num_vars() == num_vars {
+        true => Ok(bdd),
+        false => Err("A BDD of the checkpoint has an unexpected number of variables.".to_string()),
+    }
+}
+
+fn read_array<R: Read, const N: usize>(input: &mut R) -> Result<[u8; N], String> {
+    let mut buffer = [0u8; N];
+    read_exact(input, &mut buffer)?;
+    Ok(buffer)
+}
+
+fn read_exact<R: Read>(input: &mut R, buffer: &mut [u8]) -> Result<(), String> {
+    input
+        .read_exact(buffer)
+        .map_err(|_| "The checkpoint is truncated.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Config;
+    use crate::chain::chain;
+    use crate::test_utils::cycle_network;
+    use crate::test_utils::skip_large_model;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::FnUpdate;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use test_generator::test_resources;
+
+    #[test]
+    fn checkpoint_save_and_load() {
+        let graph = SymbolicAsyncGraph::new(&cycle_network()).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("scc-checkpoint-{}.checkpoint", std::process::id()));
+
+        let mut saved = Vec::new();
+        let sccs = chain_from_checkpoint(&graph, Checkpoint::new(&graph, TrimLvl::None), 1, |it| {
+            saved.push(it.clone())
+        });
+        assert_eq!(sccs.len(), 1);
+        assert!(saved.last().unwrap().is_finished());
+
+        for checkpoint in &saved {
+            checkpoint.save(&path).unwrap();
+            assert_eq!(&Checkpoint::load(&graph, &path).unwrap(), checkpoint);
+        }
+
+        // a graph with a different number of variables
+        let bn = BooleanNetwork::try_from("A -> A\n$A: A").unwrap();
+        let other = SymbolicAsyncGraph::new(&bn).unwrap();
+        assert!(Checkpoint::load(&other, &path).is_err());
+
+        // a different network with the same variables
+        let mut bn = cycle_network();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+        bn.set_update_function(var_c, Some(FnUpdate::mk_var(var_c)))
+            .unwrap();
+        let other = SymbolicAsyncGraph::new(&bn.infer_valid_graph().unwrap()).unwrap();
+        assert!(Checkpoint::load(&other, &path).is_err());
+
+        // different restrictions of the same network
+        let var_a = graph.variables().next().unwrap();
+        let restricted = graph.restrict(&graph.mk_subspace(&[(var_a, true)]));
+        Checkpoint::new(&restricted, TrimLvl::None)
+            .save(&path)
+            .unwrap();
+        assert!(Checkpoint::load(&restricted, &path).is_ok());
+        let other = graph.restrict(&graph.mk_subspace(&[(var_a, false)]));
+        assert!(Checkpoint::load(&other, &path).is_err());
+        assert!(Checkpoint::load(&graph, &path).is_err());
+
+        // the temporary file never replaces a sibling file, even if `path` itself ends with
+        // `.tmp`
+        let tmp_path = path.with_extension("tmp");
+        saved[0].save(&tmp_path).unwrap();
+        saved.last().unwrap().save(&path).unwrap();
+        assert_eq!(&Checkpoint::load(&graph, &tmp_path).unwrap(), &saved[0]);
+        saved[1].save(&tmp_path).unwrap();
+        assert_eq!(&Checkpoint::load(&graph, &tmp_path).unwrap(), &saved[1]);
+        assert_eq!(
+            &Checkpoint::load(&graph, &path).unwrap(),
+            saved.last().unwrap()
+        );
+        std::fs::remove_file(&tmp_path).unwrap();
+
+        // a corrupted file
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(Checkpoint::load(&graph, &path).is_err());
+        std::fs::write(&path, b"not a checkpoint").unwrap();
+        assert!(Checkpoint::load(&graph, &path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(Checkpoint::load(&graph, &path).is_err());
+    }
+
+    #[test_resources("./models/bbm-inputs-true/*.aeon")]
+    fn compare_resumed_chain_with_chain(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        for trim_lvl in [TrimLvl::None, TrimLvl::StartOnly, TrimLvl::Full] {
+            let config = Config {
+                trim_lvl,
+                ..Default::default()
+            };
+            let expected = chain(graph.clone(), config).collect::<Vec<_>>();
+
+            let mut saved = Vec::new();
+            let sccs = chain_from_checkpoint(&graph, Checkpoint::new(&graph, trim_lvl), 1, |it| {
+                saved.push(it.clone())
+            });
+            assert_eq!(sccs, expected);
+
+            // resume (after a round trip through the binary format) from a few checkpoints
+            for checkpoint in saved.iter().step_by(saved.len() / 3 + 1) {
+                let mut bytes = Vec::new();
+                checkpoint.write(&mut bytes).unwrap();
+                let restored = Checkpoint::read(&graph, &mut bytes.as_slice()).unwrap();
+                assert_eq!(&restored, checkpoint);
+
+                let resumed = chain_from_checkpoint(&graph, restored, 1000, |_| ());
+                assert_eq!(resumed, expected);
+            }
+        }
+    }
+}
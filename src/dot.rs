@@ -0,0 +1,210 @@
+//! Export of (small) SCCs and of the SCC condensation in the Graphviz DOT format.
+
+use crate::analysis::OscillationProfile;
+use crate::analysis::SccClassification;
+use crate::chain::fwd_saturation;
+use crate::transition_system::SymbolicTransitionSystem;
+use crate::witness::flipped_variables;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// The explicit state-transition graph of the `scc` (transitions leaving the `scc` are omitted).
+///
+/// Every state is labelled by its valuation (one digit per variable, the variable order is
+/// given in the graph label) and every transition by the variables it flips. Fails if
+/// the `scc` has more than `max_states` states or more than one color.
+pub fn scc_to_dot<G: SymbolicTransitionSystem>(
+    graph: &G,
+    scc: &GraphColoredVertices,
+    max_states: usize,
+) -> Result<String, String> {
+    if !scc.colors().is_singleton() {
+        return Err("The SCC must have exactly one color.".to_string());
+    }
+    let size = scc.exact_cardinality();
+    if size > max_states.into() {
+        return Err(format!(
+            "The SCC has {size} states, but at most {max_states} are allowed."
+        ));
+    }
+
+    let states = singletons(scc);
+    let index = states
+        .iter()
+        .enumerate()
+        .map(|(i, state)| (state.clone(), i))
+        .collect::<HashMap<_, _>>();
+
+    let context = graph.symbolic_context();
+    let names = graph
+        .variables()
+        .into_iter()
+        .map(|var| context.get_network_variable_name(var))
+        .collect::<Vec<_>>();
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph scc {{").unwrap();
+    writeln!(dot, "  label=\"{}\";", names.join(" ")).unwrap();
+    for (i, state) in states.iter().enumerate() {
+        writeln!(dot, "  s{i} [label=\"{}\"];", valuation(graph, state)).unwrap();
+    }
+    for (i, state) in states.iter().enumerate() {
+        for successor in singletons(&graph.post(state).intersect(scc)) {
+            let flipped = flipped_variables(graph, state, &successor)
+                .into_iter()
+                .map(|var| context.get_network_variable_name(var))
+                .collect::<Vec<_>>();
+            writeln!(
+                dot,
+                "  s{i} -> s{} [label=\"{}\"];",
+                index[&successor],
+                flipped.join(", ")
+            )
+            .unwrap();
+        }
+    }
+    writeln!(dot, "}}").unwrap();
+
+    Ok(dot)
+}
+
+/// The condensation of the given SCCs: one node per SCC and an edge whenever one SCC can reach
+/// another one (transitively reduced, so reachability through a third SCC is not drawn).
+///
+/// Every SCC is labelled by its index, size and oscillation profile; the terminal SCCs
+/// (attractors) have a double border. The `sccs` need not cover the whole state space.
+pub fn condensation_to_dot<G: SymbolicTransitionSystem>(
+    graph: &G,
+    sccs: &[GraphColoredVertices],
+) -> String {
+    let reaches = sccs
+        .iter()
+        .map(|scc| {
+            let reachable = fwd_saturation(graph, scc);
+            sccs.iter()
+                .map(|other| !reachable.intersect(other).is_empty())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let is_edge = |i: usize, j: usize| {
+        i != j
+            && reaches[i][j]
+            && !(0..sccs.len()).any(|k| k != i && k != j && reaches[i][k] && reaches[k][j])
+    };
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph condensation {{").unwrap();
+    for (i, scc) in sccs.iter().enumerate() {
+        let profile = OscillationProfile::new(graph, scc);
+        let peripheries = match SccClassification::new(graph, scc).terminal {
+            true => 2,
+            false => 1,
+        };
+        writeln!(
+            dot,
+            "  scc{i} [label=\"#{i}: {} {profile}\", peripheries={peripheries}];",
+            scc.exact_cardinality()
+        )
+        .unwrap();
+    }
+    for i in 0..sccs.len() {
+        for j in (0..sccs.len()).filter(|j| is_edge(i, *j)) {
+            writeln!(dot, "  scc{i} -> scc{j};").unwrap();
+        }
+    }
+    writeln!(dot, "}}").unwrap();
+
+    dot
+}
+
+/// Splits the `set` into singletons (in the order given by `pick_singleton`).
+fn singletons(set: &GraphColoredVertices) -> Vec<GraphColoredVertices> {
+    let mut remaining = set.clone();
+    let mut result = Vec::new();
+    while !remaining.is_empty() {
+        let state = remaining.pick_singleton();
+        remaining = remaining.minus(&state);
+        result.push(state);
+    }
+    result
+}
+
+/// The valuation of a singleton `state`, one digit per variable.
+fn valuation<G: SymbolicTransitionSystem>(graph: &G, state: &GraphColoredVertices) -> String {
+    graph
+        .variables()
+        .into_iter()
+        .map(
+            |var| match state.fix_network_variable(var, true).is_empty() {
+                true => '0',
+                false => '1',
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::cycle_network;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+    #[test]
+    fn dot_scc() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+        let scc = graph.mk_subspace(&[(var_c, false)]);
+
+        let dot = scc_to_dot(&graph, &scc, 4).unwrap();
+        assert!(dot.starts_with("digraph scc {\n  label=\"A B C\";\n"));
+        for state in ["000", "100", "110", "010"] {
+            assert!(dot.contains(&format!("[label=\"{state}\"];")));
+        }
+        assert_eq!(dot.matches("[label=\"A\"];").count(), 2);
+        assert_eq!(dot.matches("[label=\"B\"];").count(), 2);
+        assert_eq!(dot.matches(" -> ").count(), 4);
+
+        assert!(scc_to_dot(&graph, &scc, 3).is_err());
+    }
+
+    #[test]
+    fn dot_condensation() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+        let state = |a, b| graph.mk_subspace(&[(var_a, a), (var_b, b), (var_c, true)]);
+
+        // the cycle -> 111 -> (101 or 011) -> 001
+        let sccs = vec![
+            graph.mk_subspace(&[(var_c, false)]),
+            state(true, true),
+            state(true, false),
+            state(false, true),
+            state(false, false),
+        ];
+        let dot = condensation_to_dot(&graph, &sccs);
+
+        assert!(dot.contains("scc0 [label=\"#0: 4 **0\", peripheries=1];"));
+        assert!(dot.contains("scc4 [label=\"#4: 1 001\", peripheries=2];"));
+        let mut edges = dot
+            .lines()
+            .filter(|it| it.contains("->"))
+            .collect::<Vec<_>>();
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![
+                "  scc0 -> scc1;",
+                "  scc1 -> scc2;",
+                "  scc1 -> scc3;",
+                "  scc2 -> scc4;",
+                "  scc3 -> scc4;",
+            ]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! Explicit-state SCC decomposition, intended as a ground-truth oracle for small networks.
+//!
+//! The asynchronous state-transition graph is built by evaluating the update functions
+//! of the network state by state (no BDDs are involved), and then decomposed using
+//! Tarjan's algorithm. The resulting SCCs can be converted back to
+//! `GraphColoredVertices` to be compared with the output of the symbolic algorithms.
+//!
+//! Keep in mind the state space is exponential in the number of network variables.
+
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use std::collections::HashMap;
+
+/// Explicit asynchronous state-transition graph of a non-parametrized Boolean network.
+///
+/// A state is represented by its index; the value of the `i`-th network variable is
+/// the `i`-th bit of the index.
+#[derive(Clone, Debug)]
+pub struct ExplicitAsyncGraph {
+    variables: Vec<VariableId>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl ExplicitAsyncGraph {
+    pub fn new(network: &BooleanNetwork) -> Result<Self, String> {
+        if network.num_parameters() > 0 || network.num_implicit_parameters() > 0 {
+            return Err("The network must not be parametrized.".to_string());
+        }
+        if network.num_vars() >= usize::BITS as usize {
+            return Err(format!(
+                "Cannot enumerate the states of a network with {} variables.",
+                network.num_vars()
+            ));
+        }
+
+        let variables = network.variables().collect::<Vec<_>>();
+        let successors = (0..1usize << variables.len())
+            .map(|state| {
+                let values = variables
+                    .iter()
+                    .enumerate()
+                    .map(|(bit, var)| (*var, state & (1 << bit) != 0))
+                    .collect::<HashMap<_, _>>();
+
+                variables
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, var)| {
+                        let function = network
+                            .get_update_function(**var)
+                            .as_ref()
+                            .expect("no implicit parameters");
+                        let next = function.evaluate(&values).expect("no parameters");
+                        next != values[*var]
+                    })
+                    .map(|(bit, _)| state ^ (1 << bit))
+                    .collect()
+            })
+            .collect();
+
+        Ok(ExplicitAsyncGraph {
+            variables,
+            successors,
+        })
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.successors.len()
+    }
+
+    pub fn successors(&self, state: usize) -> &[usize] {
+        &self.successors[state]
+    }
+
+    /// The value of every network variable in the given `state`.
+    pub fn valuation(&self, state: usize) -> Vec<(VariableId, bool)> {
+        self.variables
+            .iter()
+            .enumerate()
+            .map(|(bit, var)| (*var, state & (1 << bit) != 0))
+            .collect()
+    }
+
+    /// All the states reachable from `state` (including `state` itself), in ascending order.
+    pub fn reachable(&self, state: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.num_states()];
+        let mut stack = vec![state];
+        visited[state] = true;
+        while let Some(state) = stack.pop() {
+            for successor in self.successors(state) {
+                if !visited[*successor] {
+                    visited[*successor] = true;
+                    stack.push(*successor);
+                }
+            }
+        }
+
+        (0..self.num_states()).filter(|it| visited[*it]).collect()
+    }
+
+    /// All the SCCs of the graph (including the trivial ones), computed by Tarjan's
+    /// algorithm.
+    ///
+    /// The SCCs are returned in reverse topological order (an SCC is listed before
+    /// every SCC that can reach it).
+    pub fn scc_decomposition(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let mut index = vec![UNVISITED; self.num_states()];
+        let mut low_link = vec![0; self.num_states()];
+        let mut on_stack = vec![false; self.num_states()];
+        let mut scc_stack = Vec::new();
+        let mut next_index = 0;
+        let mut output = Vec::new();
+
+        for root in 0..self.num_states() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+
+            // the "recursion" stack; a state and the position of its next successor
+            let mut call_stack = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            scc_stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((state, successor_position)) = call_stack.last_mut() {
+                let state = *state;
+
+                if let Some(successor) = self.successors[state].get(*successor_position) {
+                    *successor_position += 1;
+                    let successor = *successor;
+
+                    if index[successor] == UNVISITED {
+                        // "recursive call"
+                        index[successor] = next_index;
+                        low_link[successor] = next_index;
+                        next_index += 1;
+                        scc_stack.push(successor);
+                        on_stack[successor] = true;
+                        call_stack.push((successor, 0));
+                    } else if on_stack[successor] {
+                        low_link[state] = low_link[state].min(index[successor]);
+                    }
+                    continue;
+                }
+
+                // all successors processed -> "return"
+                call_stack.pop();
+                if let Some((parent, _)) = call_stack.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[state]);
+                }
+
+                if low_link[state] == index[state] {
+                    let mut scc = Vec::new();
+                    loop {
+                        let member = scc_stack.pop().expect("state is on the stack");
+                        on_stack[member] = false;
+                        scc.push(member);
+                        if member == state {
+                            break;
+                        }
+                    }
+                    output.push(scc);
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Converts the given `states` into a symbolic set of the `graph`.
+    ///
+    /// The `graph` must be built from the same network as this graph.
+    pub fn to_symbolic(
+        &self,
+        graph: &SymbolicAsyncGraph,
+        states: &[usize],
+    ) -> GraphColoredVertices {
+        states
+            .iter()
+            .map(|state| graph.mk_subspace(&self.valuation(*state)))
+            .fold(graph.mk_empty_colored_vertices(), |acc, it| acc.union(&it))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Config;
+    use crate::chain::chain;
+    use crate::test_utils::skip_large_model;
+    use std::collections::HashSet;
+    use test_generator::test_resources;
+
+    #[test]
+    fn explicit_basic_graph() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -| A
+            B -> B
+            $A: !A
+            $B: B
+            "#,
+        )
+        .unwrap();
+        let explicit = ExplicitAsyncGraph::new(&bn).unwrap();
+
+        assert_eq!(explicit.num_states(), 4);
+        // only `A` can change (bit 0)
+        assert_eq!(explicit.successors(0b00), &[0b01]);
+        assert_eq!(explicit.successors(0b10), &[0b11]);
+        assert_eq!(explicit.reachable(0b00), vec![0b00, 0b01]);
+
+        let mut sccs = explicit.scc_decomposition();
+        sccs.iter_mut().for_each(|scc| scc.sort());
+        sccs.sort();
+        assert_eq!(sccs, vec![vec![0b00, 0b01], vec![0b10, 0b11]]);
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        assert_eq!(
+            explicit.to_symbolic(&graph, &sccs[0]),
+            graph.mk_subspace(&[(var_b, false)])
+        );
+    }
+
+    #[test]
+    fn explicit_topological_order() {
+        // only trivial SCCs; `A` goes up, `B` follows `A`
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> B
+            $A: true
+            $B: A
+            "#,
+        )
+        .unwrap();
+        let explicit = ExplicitAsyncGraph::new(&bn).unwrap();
+
+        let sccs = explicit.scc_decomposition();
+        let position = |state: usize| sccs.iter().position(|it| it.contains(&state)).unwrap();
+
+        assert_eq!(sccs.len(), 4);
+        assert!(position(0b11) < position(0b01));
+        assert!(position(0b01) < position(0b00));
+        assert!(position(0b11) < position(0b10));
+    }
+
+    #[test]
+    fn explicit_parametrized_network() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> B
+            $A: true
+            "#,
+        )
+        .unwrap();
+
+        assert!(ExplicitAsyncGraph::new(&bn).is_err());
+    }
+
+    #[test_resources("./models/bbm-inputs-true/*.aeon")]
+    fn compare_chain_explicit_selected(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let explicit = ExplicitAsyncGraph::new(&bn).unwrap();
+
+        let explicit_scc_set = explicit
+            .scc_decomposition()
+            .into_iter()
+            .filter(|scc| scc.len() > 1)
+            .map(|scc| explicit.to_symbolic(&graph, &scc))
+            .collect::<HashSet<_>>();
+
+        let chain_scc_set = chain(graph, Config::default()).collect::<HashSet<_>>();
+
+        assert_eq!(chain_scc_set, explicit_scc_set);
+    }
+}
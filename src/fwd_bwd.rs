@@ -1,17 +1,17 @@
 //! used just for integration tests - to compare the output of chain on large (non-manual) datasets
 
 use crate::assert_precondition_graph_not_colored;
+use crate::transition_system::SymbolicTransitionSystem;
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
 use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
-use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
 
-pub fn fwd_bwd_scc_decomposition_naive(
-    graph: SymbolicAsyncGraph,
+pub fn fwd_bwd_scc_decomposition_naive<G: SymbolicTransitionSystem>(
+    graph: G,
 ) -> impl Iterator<Item = GraphColoredVertices> {
     assert_precondition_graph_not_colored(&graph);
 
     let mut scc_dump = Vec::new();
-    let mut remaining_space = graph.mk_unit_colored_vertices();
+    let mut remaining_space = graph.unit_colored_vertices().clone();
     while !remaining_space.is_empty() {
         let scc = get_some_scc_naive(&graph, &remaining_space);
         remaining_space = remaining_space.minus(&scc);
@@ -25,8 +25,8 @@ pub fn fwd_bwd_scc_decomposition_naive(
     scc_dump.into_iter()
 }
 
-fn get_some_scc_naive(
-    graph: &SymbolicAsyncGraph,
+fn get_some_scc_naive<G: SymbolicTransitionSystem>(
+    graph: &G,
     space_to_pick_from: &GraphColoredVertices,
 ) -> GraphColoredVertices {
     assert!(!space_to_pick_from.is_empty());
@@ -44,7 +44,10 @@ fn get_some_scc_naive(
 }
 
 // SymbolicAsyncGraph::reach_forward optimized; use this naive approach for better comparison
-fn naive_fwd(graph: &SymbolicAsyncGraph, pivot: &GraphColoredVertices) -> GraphColoredVertices {
+fn naive_fwd<G: SymbolicTransitionSystem>(
+    graph: &G,
+    pivot: &GraphColoredVertices,
+) -> GraphColoredVertices {
     let mut result = pivot.clone();
     let mut curr_layer = pivot.clone();
 
@@ -62,7 +65,10 @@ fn naive_fwd(graph: &SymbolicAsyncGraph, pivot: &GraphColoredVertices) -> GraphC
 }
 
 // SymbolicAsyncGraph::reach_backward optimized; use this naive approach for better comparison
-fn naive_bwd(graph: &SymbolicAsyncGraph, pivot: &GraphColoredVertices) -> GraphColoredVertices {
+fn naive_bwd<G: SymbolicTransitionSystem>(
+    graph: &G,
+    pivot: &GraphColoredVertices,
+) -> GraphColoredVertices {
     let mut result = pivot.clone();
     let mut curr_layer = pivot.clone();
 
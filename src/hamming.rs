@@ -1,12 +1,43 @@
+//! Hamming-distance operations on symbolic sets of states.
+//!
+//! The distance of two states is the number of network variables in which they differ.
+//! Only the state variables are counted: two colored states are comparable only if they
+//! have the same color, and any extra symbolic variables are ignored. The distance of
+//! a state to a set is its distance to the closest state of the set.
+
+use crate::transition_system::SymbolicTransitionSystem;
 use biodivine_lib_bdd::Bdd;
+use biodivine_lib_bdd::BddNode;
+use biodivine_lib_bdd::BddPartialValuation;
 use biodivine_lib_bdd::BddPointer;
 use biodivine_lib_bdd::BddValuation;
 use biodivine_lib_bdd::BddVariable;
+use biodivine_lib_param_bn::RegulatoryGraph;
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
 use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use std::cmp::Reverse;
+use std::collections::HashMap;
 
 pub trait Hamming {
+    /// The state of `choice_set` that is the furthest from `self` (which must be a singleton).
     fn ham_furthest_within(&self, choice_set: &GraphColoredVertices) -> GraphColoredVertices;
+
+    /// Same as [Hamming::ham_furthest_within], but a differing variable contributes its
+    /// weight instead of one. The `weights` are indexed by the network variables of `graph`.
+    fn ham_furthest_within_weighted<G: SymbolicTransitionSystem>(
+        &self,
+        graph: &G,
+        choice_set: &GraphColoredVertices,
+        weights: &[usize],
+    ) -> GraphColoredVertices;
+
+    /// Color-aware version of [Hamming::ham_furthest_within]: `self` must contain exactly one
+    /// state for every one of its colors. See [furthest_within_per_color].
+    fn ham_furthest_within_colored<G: SymbolicTransitionSystem>(
+        &self,
+        graph: &G,
+        choice_set: &GraphColoredVertices,
+    ) -> GraphColoredVertices;
 }
 
 impl Hamming for GraphColoredVertices {
@@ -15,32 +46,424 @@ impl Hamming for GraphColoredVertices {
         assert!(self.is_singleton());
 
         let self_singleton_valuation = self.vertices().as_bdd().sat_witness().unwrap();
+        // the singleton fixes exactly the variables that identify a state; any other
+        // (extra) symbolic variables must remain unconstrained in the result as well
+        let self_singleton_clause = self.as_bdd().first_clause().unwrap();
+
+        // every symbolic variable counts
+        let bdd_var_weights = vec![1; self_singleton_valuation.to_values().len()];
+
+        let res = max_dist(
+            choice_set,
+            &self_singleton_valuation,
+            &self_singleton_clause,
+            &bdd_var_weights,
+        );
+        assert!(res.is_singleton());
+
+        res
+    }
+
+    fn ham_furthest_within_weighted<G: SymbolicTransitionSystem>(
+        &self,
+        graph: &G,
+        choice_set: &GraphColoredVertices,
+        weights: &[usize],
+    ) -> GraphColoredVertices {
+        assert!(!choice_set.is_empty());
+        assert!(self.is_singleton());
+
+        let variables = graph.variables();
+        assert_eq!(weights.len(), variables.len());
+
+        let self_singleton_valuation = self.vertices().as_bdd().sat_witness().unwrap();
+        let self_singleton_clause = self.as_bdd().first_clause().unwrap();
+
+        // only the state variables count
+        let context = graph.symbolic_context();
+        let mut bdd_var_weights = vec![0; self_singleton_valuation.to_values().len()];
+        for (var, weight) in variables.into_iter().zip(weights) {
+            bdd_var_weights[context.get_state_variable(var).to_index()] = *weight;
+        }
 
-        let res = max_dist(choice_set, &self_singleton_valuation);
+        let res = max_dist(
+            choice_set,
+            &self_singleton_valuation,
+            &self_singleton_clause,
+            &bdd_var_weights,
+        );
         assert!(res.is_singleton());
 
         res
     }
+
+    fn ham_furthest_within_colored<G: SymbolicTransitionSystem>(
+        &self,
+        graph: &G,
+        choice_set: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        assert!(!choice_set.is_empty());
+        assert_eq!(&self.pick_vertex(), self);
+
+        furthest_within_per_color(graph, self, choice_set)
+    }
+}
+
+/// Weights for [Hamming::ham_furthest_within_weighted] favouring the variables that
+/// regulate many others: the weight of a variable is one plus its number of targets.
+pub fn out_degree_weights(graph: &RegulatoryGraph) -> Vec<usize> {
+    graph
+        .variables()
+        .map(|var| 1 + graph.targets(var).len())
+        .collect()
+}
+
+/// All states of the `graph` at distance at most `k` from `set`.
+pub fn hamming_ball<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: &GraphColoredVertices,
+    k: usize,
+) -> GraphColoredVertices {
+    let balls = hamming_balls(graph, set);
+    balls[k.min(balls.len() - 1)].intersect(graph.unit_colored_vertices())
+}
+
+/// All states of the `graph` at distance exactly `k` from `set`.
+pub fn hamming_sphere<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: &GraphColoredVertices,
+    k: usize,
+) -> GraphColoredVertices {
+    let balls = hamming_balls(graph, set);
+    let ball = |k: usize| &balls[k.min(balls.len() - 1)];
+    let sphere = match k {
+        0 => ball(0).clone(),
+        _ => ball(k).minus(ball(k - 1)),
+    };
+
+    sphere.intersect(graph.unit_colored_vertices())
+}
+
+/// The smallest distance between a state of `set_a` and a state of `set_b`.
+///
+/// Returns `None` if there is no such pair (in particular, if the sets have no common color).
+pub fn hamming_distance<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set_a: &GraphColoredVertices,
+    set_b: &GraphColoredVertices,
+) -> Option<usize> {
+    let mut min_dist = MinDist::new(graph, set_a.as_bdd(), set_b.as_bdd());
+    min_dist.solve(set_a.as_bdd().root_pointer(), set_b.as_bdd().root_pointer())
+}
+
+/// A state of `choice_set` that is the closest to `pivot_set`.
+///
+/// Returns an empty set if no state of `choice_set` shares a color with `pivot_set`.
+pub fn closest_within<G: SymbolicTransitionSystem>(
+    graph: &G,
+    pivot_set: &GraphColoredVertices,
+    choice_set: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    let (choice_bdd, pivot_bdd) = (choice_set.as_bdd(), pivot_set.as_bdd());
+    let mut min_dist = MinDist::new(graph, choice_bdd, pivot_bdd);
+    if min_dist
+        .solve(choice_bdd.root_pointer(), pivot_bdd.root_pointer())
+        .is_none()
+    {
+        return graph.empty_colored_vertices().clone();
+    }
+
+    // follow the optimal pair of paths, fixing the values on the side of the choice set
+    let mut fixed_values = Vec::new();
+    let mut pointers = (choice_bdd.root_pointer(), pivot_bdd.root_pointer());
+    while let Some(Some((_, step))) = min_dist.cache.get(&pointers) {
+        let Some((var, choice_value, pivot_value)) = *step else {
+            break;
+        };
+        fixed_values.push((var, choice_value));
+        pointers = (
+            min_dist.child(choice_bdd, pointers.0, var, choice_value),
+            min_dist.child(pivot_bdd, pointers.1, var, pivot_value),
+        );
+    }
+
+    choice_set
+        .copy(choice_bdd.select(&fixed_values))
+        .pick_singleton()
+}
+
+/// A state of `choice_set` that is the furthest from `pivot_set`.
+///
+/// Only the colors of `pivot_set` are considered; returns an empty set if no state of
+/// `choice_set` shares a color with `pivot_set`.
+pub fn furthest_within<G: SymbolicTransitionSystem>(
+    graph: &G,
+    pivot_set: &GraphColoredVertices,
+    choice_set: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    let candidates = choice_set.intersect_colors(&pivot_set.colors());
+
+    // the candidates outside of the largest ball around the pivot that does not cover all
+    // of them are the furthest ones (if every candidate is a pivot, any of them is)
+    let mut furthest = candidates.clone();
+    for ball in hamming_balls(graph, pivot_set) {
+        let outside = candidates.minus(&ball);
+        if outside.is_empty() {
+            break;
+        }
+        furthest = outside;
+    }
+
+    furthest.pick_singleton()
+}
+
+/// For every color of `pivot_set`, a state of `choice_set` that is the furthest from
+/// `pivot_set` (within that color).
+///
+/// The result contains exactly one state for every color shared by both sets; the colors
+/// are never enumerated.
+pub fn furthest_within_per_color<G: SymbolicTransitionSystem>(
+    graph: &G,
+    pivot_set: &GraphColoredVertices,
+    choice_set: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    let candidates = choice_set.intersect_colors(&pivot_set.colors());
+    let mut remaining = candidates.clone();
+    let mut furthest = graph.empty_colored_vertices().clone();
+
+    // in every color, the last candidates to be covered by the growing balls around the pivot
+    // are the furthest ones (if every candidate of the color is a pivot, any of them is)
+    for ball in hamming_balls(graph, pivot_set) {
+        let next_remaining = remaining.minus(&ball);
+
+        let covered_colors = remaining.colors().minus(&next_remaining.colors());
+        furthest = furthest.union(&remaining.intersect_colors(&covered_colors).pick_vertex());
+
+        remaining = next_remaining;
+        if remaining.is_empty() {
+            break;
+        }
+    }
+
+    // the candidates no ball ever covers (their extra symbolic variables differ)
+    furthest.union(&remaining.pick_vertex())
+}
+
+/// The Hamming balls around `set`: the `j`-th ball contains the states (of the colors of `set`)
+/// at distance at most `j` from `set`. The balls beyond the last one are all equal to it.
+///
+/// Computed in a single bottom-up pass over the BDD of `set` (see [max_dist_path_bottom_up]):
+/// for every node, the balls around the valuations of the variables below it that lead to
+/// the `true` terminal. A valuation is within distance `j` of a node if it is within
+/// distance `j` of the child given by its value of the node's variable, or, for a state
+/// variable, within distance `j - 1` of the other child. The variables that a path skips
+/// can take any value, so they never add to the distance.
+fn hamming_balls<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: &GraphColoredVertices,
+) -> Vec<GraphColoredVertices> {
+    let context = graph.symbolic_context();
+    let bdd_variables = context.bdd_variable_set();
+    let mut is_state_variable = vec![false; bdd_variables.num_vars() as usize];
+    for var in context.state_variables() {
+        is_state_variable[var.to_index()] = true;
+    }
+
+    let set_nodes = set.as_bdd().clone().to_nodes();
+
+    // the `false` terminal (index 0) has no ball but the empty one; the only ball of
+    // the `true` terminal (index 1, if present) contains everything
+    let mut balls = vec![vec![bdd_variables.mk_false()]; set_nodes.len()];
+    if let Some(one_balls) = balls.get_mut(1) {
+        *one_balls = vec![bdd_variables.mk_true()];
+    }
+
+    let mut decision_nodes = (2..set_nodes.len()).collect::<Vec<_>>();
+    decision_nodes.sort_unstable_by_key(|it| Reverse(set_nodes[*it].var));
+
+    for node_idx in decision_nodes {
+        let node = set_nodes[node_idx];
+        let low_balls = &balls[node.low_link.to_index()];
+        let high_balls = &balls[node.high_link.to_index()];
+        let child_ball = |child_balls: &'_ [Bdd], j: usize| -> Bdd {
+            child_balls[j.min(child_balls.len() - 1)].clone()
+        };
+
+        // flipping a state variable grows the balls of the children by one more step
+        let is_state = is_state_variable[node.var.to_index()];
+        let num_balls = low_balls.len().max(high_balls.len()) + usize::from(is_state);
+        let is_true = bdd_variables.mk_var(node.var);
+
+        let mut node_balls = Vec::<Bdd>::with_capacity(num_balls);
+        for j in 0..num_balls {
+            let (mut high, mut low) = (child_ball(high_balls, j), child_ball(low_balls, j));
+            if is_state && j > 0 {
+                high = high.or(&child_ball(low_balls, j - 1));
+                low = low.or(&child_ball(high_balls, j - 1));
+            }
+
+            let ball = Bdd::if_then_else(&is_true, &high, &low);
+            if node_balls.last() == Some(&ball) {
+                // once a ball does not grow, neither does any larger one
+                break;
+            }
+            node_balls.push(ball);
+        }
+
+        balls[node_idx] = node_balls;
+    }
+
+    balls
+        .swap_remove(set.as_bdd().root_pointer().to_index())
+        .into_iter()
+        .map(|ball| set.copy(ball))
+        .collect()
+}
+
+/// A step of the optimal pair of paths: the branching variable and the values chosen
+/// in the first and in the second BDD. `None` once both paths reach the `true` terminal.
+type MinDistStep = Option<(BddVariable, bool, bool)>;
+
+/// The smallest distance between a valuation of one BDD and a valuation of another,
+/// computed over pairs of their nodes.
+struct MinDist<'a> {
+    bdd_a: &'a Bdd,
+    bdd_b: &'a Bdd,
+    is_state_variable: Vec<bool>,
+    cache: HashMap<(BddPointer, BddPointer), Option<(usize, MinDistStep)>>,
+}
+
+impl<'a> MinDist<'a> {
+    fn new<G: SymbolicTransitionSystem>(graph: &G, bdd_a: &'a Bdd, bdd_b: &'a Bdd) -> Self {
+        let context = graph.symbolic_context();
+        let mut is_state_variable = vec![false; context.bdd_variable_set().num_vars() as usize];
+        for var in context.state_variables() {
+            is_state_variable[var.to_index()] = true;
+        }
+
+        MinDist {
+            bdd_a,
+            bdd_b,
+            is_state_variable,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The `value`-child of `node` of the `bdd` w.r.t. `var` (`node` itself if it does not
+    /// test `var`).
+    fn child(&self, bdd: &Bdd, node: BddPointer, var: BddVariable, value: bool) -> BddPointer {
+        if node.is_terminal() || bdd.var_of(node) != var {
+            node
+        } else if value {
+            bdd.high_link_of(node)
+        } else {
+            bdd.low_link_of(node)
+        }
+    }
+
+    /// The variable to branch on in the pair of nodes (at least one of them is not
+    /// a terminal), and the pairs of values the two paths may take for it.
+    fn branching(
+        &self,
+        node_a: BddPointer,
+        node_b: BddPointer,
+    ) -> (BddVariable, &'static [(bool, bool)]) {
+        let var = match (node_a.is_terminal(), node_b.is_terminal()) {
+            (true, _) => self.bdd_b.var_of(node_b),
+            (_, true) => self.bdd_a.var_of(node_a),
+            _ => self.bdd_a.var_of(node_a).min(self.bdd_b.var_of(node_b)),
+        };
+
+        // values of non-state variables (colors, extra variables) must agree
+        let choices: &[(bool, bool)] = if self.is_state_variable[var.to_index()] {
+            &[(false, false), (true, true), (false, true), (true, false)]
+        } else {
+            &[(false, false), (true, true)]
+        };
+        (var, choices)
+    }
+
+    /// The pairs of children of the pair of nodes, together with the step leading to them.
+    fn children(
+        &self,
+        node_a: BddPointer,
+        node_b: BddPointer,
+    ) -> impl Iterator<Item = (BddPointer, BddPointer, (BddVariable, bool, bool))> + '_ {
+        let (var, choices) = self.branching(node_a, node_b);
+        choices.iter().map(move |(value_a, value_b)| {
+            (
+                self.child(self.bdd_a, node_a, var, *value_a),
+                self.child(self.bdd_b, node_b, var, *value_b),
+                (var, *value_a, *value_b),
+            )
+        })
+    }
+
+    /// Solves the pairs of nodes bottom-up using an explicit stack (a pair is solved once
+    /// all the pairs of its children are), so there is no recursion depth to worry about.
+    fn solve(&mut self, node_a: BddPointer, node_b: BddPointer) -> Option<usize> {
+        // a pair of nodes, and whether the pairs of its children were already pushed
+        let mut stack = vec![(node_a, node_b, false)];
+        while let Some((node_a, node_b, expanded)) = stack.pop() {
+            if self.cache.contains_key(&(node_a, node_b)) {
+                continue;
+            }
+
+            let result = if node_a.is_zero() || node_b.is_zero() {
+                None
+            } else if node_a.is_one() && node_b.is_one() {
+                Some((0, None))
+            } else if !expanded {
+                stack.push((node_a, node_b, true));
+                for (child_a, child_b, _) in self.children(node_a, node_b) {
+                    if !self.cache.contains_key(&(child_a, child_b)) {
+                        stack.push((child_a, child_b, false));
+                    }
+                }
+                continue;
+            } else {
+                let mut best = None::<(usize, MinDistStep)>;
+                for (child_a, child_b, step) in self.children(node_a, node_b) {
+                    if let Some((dist, _)) = self.cache[&(child_a, child_b)] {
+                        let dist = dist + usize::from(step.1 != step.2);
+                        if best.is_none_or(|(best_dist, _)| dist < best_dist) {
+                            best = Some((dist, Some(step)));
+                        }
+                    }
+                }
+                best
+            };
+
+            self.cache.insert((node_a, node_b), result);
+        }
+
+        self.cache[&(node_a, node_b)].map(|(dist, _)| dist)
+    }
 }
 
 fn max_dist(
     choice_set: &GraphColoredVertices,
     pivot_singleton_valuation: &BddValuation,
+    pivot_singleton_clause: &BddPartialValuation,
+    bdd_var_weights: &[usize],
 ) -> GraphColoredVertices {
     let choice_set_bdd = choice_set.vertices().as_bdd().clone();
 
-    let mut chosen_path_cache =
-        vec![None::<(usize, ChosenChild)>; choice_set_bdd.clone().to_nodes().len()];
+    // `skipped_weight[i]` is the total weight of the variables preceding the `i`-th one
+    let skipped_weight = std::iter::once(0)
+        .chain(bdd_var_weights.iter().scan(0, |acc, it| {
+            *acc += it;
+            Some(*acc)
+        }))
+        .collect::<Vec<_>>();
 
-    rec_max_dist_path(
-        choice_set_bdd.root_pointer(),
-        &choice_set_bdd,
+    let chosen_path_cache = max_dist_path_bottom_up(
+        &choice_set_bdd.clone().to_nodes(),
         pivot_singleton_valuation,
-        &mut chosen_path_cache,
+        bdd_var_weights,
+        &skipped_weight,
     );
 
-    let chosen_path_cache = chosen_path_cache;
-
     let mut valuation = pivot_singleton_valuation.clone();
     let mut curr_var_idx = 0;
     let mut next_known_var_idx = if choice_set_bdd.root_pointer().is_one() {
@@ -88,7 +511,12 @@ fn max_dist(
         curr_var_idx += 1;
     }
 
-    let res = choice_set.copy(Bdd::from(valuation));
+    let fixed_values = pivot_singleton_clause
+        .to_values()
+        .into_iter()
+        .map(|(var, _)| (var, valuation.value(var)))
+        .collect::<Vec<_>>();
+    let res = choice_set.copy(choice_set.as_bdd().select(&fixed_values));
 
     assert!(res.is_singleton());
 
@@ -102,151 +530,88 @@ enum ChosenChild {
     NoChildAvailable,
 }
 
-/// traverses the choice_set to find the "most distant" valuation possible
-/// does not update the valuation in the root if there are variables missing "above it"
-// todo consider using iterative approach
-// - aka dynamic, bottom-up approach
-fn rec_max_dist_path(
-    curr_choice_set_node_ptr: BddPointer,
-    choice_set: &Bdd,
+/// traverses the choice_set (given by its nodes) to find the "most distant" valuation possible
+///
+/// for every node, stores the distance of the most distant path to the `true` terminal,
+/// together with the child to choose; `None` if there is no such path
+/// does not account for the variables missing "above" the root
+///
+/// the nodes are processed bottom-up (by decreasing variable), so every node is solved
+/// after both of its children; this is iterative, so there is no recursion depth to worry about
+fn max_dist_path_bottom_up(
+    choice_set_nodes: &[BddNode],
     pivot_singleton_valuation: &BddValuation,
-    chosen_path_cache: &mut Vec<Option<(usize, ChosenChild)>>,
-) {
-    if chosen_path_cache[curr_choice_set_node_ptr.to_index()].is_some() {
-        return; // already "solved"
-    }
-
-    if curr_choice_set_node_ptr.is_zero() {
-        // should already be set to `None` - do this just to be sure
-        chosen_path_cache[curr_choice_set_node_ptr.to_index()] = None;
-        return;
+    bdd_var_weights: &[usize],
+    skipped_weight: &[usize],
+) -> Vec<Option<(usize, ChosenChild)>> {
+    let mut chosen_path_cache = vec![None::<(usize, ChosenChild)>; choice_set_nodes.len()];
+
+    // the `false` terminal (index 0) remains `None`; the `true` terminal (index 1, if present)
+    // signals that this branch should be considered
+    if let Some(one_cache) = chosen_path_cache.get_mut(1) {
+        *one_cache = Some((0 /* no distance */, ChosenChild::NoChildAvailable));
     }
 
-    if curr_choice_set_node_ptr.is_one() {
-        // signal that this is branch should be considered -> set to Some
-        chosen_path_cache[curr_choice_set_node_ptr.to_index()] =
-            Some((0 /* no distance */, ChosenChild::NoChildAvailable));
-        return;
-    }
-
-    let low_child_ptr = choice_set.low_link_of(curr_choice_set_node_ptr);
-    let high_child_ptr = choice_set.high_link_of(curr_choice_set_node_ptr);
-
-    // ensure children's caches computed
-    rec_max_dist_path(
-        low_child_ptr,
-        choice_set,
-        pivot_singleton_valuation,
-        chosen_path_cache,
-    );
-    rec_max_dist_path(
-        high_child_ptr,
-        choice_set,
-        pivot_singleton_valuation,
-        chosen_path_cache,
-    );
-
-    match (
-        &chosen_path_cache[low_child_ptr.to_index()],
-        &chosen_path_cache[high_child_ptr.to_index()],
-    ) {
-        (Some((low_child_dist, _)), Some((high_child_dist, _))) => {
-            let choose_low_dist_increase =
-                if pivot_singleton_valuation.value(choice_set.var_of(curr_choice_set_node_ptr)) {
-                    // todo one of the comments is wrong - `want *n*eq`; also use matches
-                    // notice the absence of negation - want neq
-                    1
-                } else {
-                    0
-                };
-            let choose_high_dist_increase =
-                if !pivot_singleton_valuation.value(choice_set.var_of(curr_choice_set_node_ptr)) {
-                    // notice the presence of negation - want neq
-                    1
-                } else {
-                    0
+    let mut decision_nodes = (2..choice_set_nodes.len()).collect::<Vec<_>>();
+    decision_nodes.sort_unstable_by_key(|it| Reverse(choice_set_nodes[*it].var));
+
+    for node_idx in decision_nodes {
+        let node = choice_set_nodes[node_idx];
+        let curr_var_idx = node.var.to_index();
+        let pivot_value = pivot_singleton_valuation.value(node.var);
+
+        // choosing the value opposite to the pivot adds the weight of the current variable;
+        // the variables skipped on the way to the child can always be chosen opposite as well
+        let child_dist = |child_ptr: BddPointer, value: bool| {
+            chosen_path_cache[child_ptr.to_index()].map(|(child_dist, _)| {
+                let dist_increase = match value != pivot_value {
+                    true => bdd_var_weights[curr_var_idx],
+                    false => 0,
                 };
+                let skipped_vars_dist = skipped_weight
+                    [choice_set_nodes[child_ptr.to_index()].var.to_index()]
+                .saturating_sub(skipped_weight[curr_var_idx + 1]);
 
-            let choose_low_skipped_vars_dist = choice_set
-                .var_of(low_child_ptr)
-                .to_index()
-                // subtract or 0 if rhs is larger
-                .saturating_sub(choice_set.var_of(curr_choice_set_node_ptr).to_index() + 1);
-            let choose_high_skipped_vars_dist = choice_set
-                .var_of(high_child_ptr)
-                .to_index()
-                // subtract or 0 if rhs is larger
-                .saturating_sub(choice_set.var_of(curr_choice_set_node_ptr).to_index() + 1);
-
-            let this_dist_low =
-                *low_child_dist + choose_low_dist_increase + choose_low_skipped_vars_dist;
-            let this_dist_high =
-                *high_child_dist + choose_high_dist_increase + choose_high_skipped_vars_dist;
-
-            if this_dist_low < this_dist_high {
-                chosen_path_cache[curr_choice_set_node_ptr.to_index()] =
-                    Some((this_dist_high, ChosenChild::High));
-            } else {
-                chosen_path_cache[curr_choice_set_node_ptr.to_index()] =
-                    Some((this_dist_low, ChosenChild::Low));
-            }
-        }
-
-        (Some((low_child_dist, _)), None) => {
-            let choose_low_dist_increase =
-                if pivot_singleton_valuation.value(choice_set.var_of(curr_choice_set_node_ptr)) {
-                    // notice the absence of negation - want neq
-                    1
-                } else {
-                    0
-                };
-            let choose_low_skipped_vars_dist = choice_set
-                .var_of(low_child_ptr)
-                .to_index()
-                // subtract or 0 if rhs is larger
-                .saturating_sub(choice_set.var_of(curr_choice_set_node_ptr).to_index() + 1);
-
-            let this_dist_low =
-                *low_child_dist + choose_low_dist_increase + choose_low_skipped_vars_dist;
-
-            chosen_path_cache[curr_choice_set_node_ptr.to_index()] =
-                Some((this_dist_low, ChosenChild::Low));
-        }
-
-        (None, Some((high_child_dist, _))) => {
-            let choose_high_dist_increase =
-                if !pivot_singleton_valuation.value(choice_set.var_of(curr_choice_set_node_ptr)) {
-                    // notice the presence of negation - want neq
-                    1
-                } else {
-                    0
-                };
-
-            let choose_high_skipped_vars_dist = choice_set
-                .var_of(high_child_ptr)
-                .to_index()
-                // subtract or 0 if rhs is larger
-                .saturating_sub(choice_set.var_of(curr_choice_set_node_ptr).to_index() + 1);
+                child_dist + dist_increase + skipped_vars_dist
+            })
+        };
 
-            let this_dist_high =
-                *high_child_dist + choose_high_dist_increase + choose_high_skipped_vars_dist;
+        let this_dist_low = child_dist(node.low_link, false);
+        let this_dist_high = child_dist(node.high_link, true);
 
-            chosen_path_cache[curr_choice_set_node_ptr.to_index()] =
-                Some((this_dist_high, ChosenChild::High));
-        }
-
-        // todo unreachable because...
-        (None, None) => unreachable!(),
+        chosen_path_cache[node_idx] = match (this_dist_low, this_dist_high) {
+            (Some(low), Some(high)) if low < high => Some((high, ChosenChild::High)),
+            (Some(low), _) => Some((low, ChosenChild::Low)),
+            (None, Some(high)) => Some((high, ChosenChild::High)),
+            // in a reduced BDD, only the `false` terminal has no path to `true`
+            (None, None) => unreachable!(),
+        };
     }
+
+    chosen_path_cache
 }
 
 #[cfg(test)]
 mod tests {
     use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::FnUpdate;
+    use biodivine_lib_param_bn::RegulatoryGraph;
     use biodivine_lib_param_bn::biodivine_std::traits::Set;
     use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicContext;
 
     use crate::hamming::Hamming;
+    use crate::hamming::closest_within;
+    use crate::hamming::furthest_within;
+    use crate::hamming::furthest_within_per_color;
+    use crate::hamming::hamming_ball;
+    use crate::hamming::hamming_distance;
+    use crate::hamming::hamming_sphere;
+    use crate::hamming::out_degree_weights;
+    use biodivine_lib_param_bn::biodivine_std::bitvector::ArrayBitVector;
+    use biodivine_lib_param_bn::biodivine_std::bitvector::BitVector;
+    use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+    use num_bigint::BigUint;
 
     fn basic_async_graph() -> SymbolicAsyncGraph {
         let bool_network = BooleanNetwork::try_from(
@@ -298,6 +663,166 @@ mod tests {
         assert_eq!(true_true.ham_furthest_within(&unit_set), false_false);
     }
 
+    #[test]
+    fn test_ham_furthest_within_weighted() {
+        let graph = four_variable_graph();
+
+        let pivot = mk_states(&graph, &[0b0000]);
+        let choice_set = mk_states(&graph, &[0b0111, 0b1000]);
+
+        // unweighted, three differing variables beat one
+        assert_eq!(
+            pivot.ham_furthest_within(&choice_set),
+            mk_states(&graph, &[0b0111])
+        );
+        assert_eq!(
+            pivot.ham_furthest_within_weighted(&graph, &choice_set, &[1, 1, 1, 1]),
+            mk_states(&graph, &[0b0111])
+        );
+        // unless the last variable is heavy enough
+        assert_eq!(
+            pivot.ham_furthest_within_weighted(&graph, &choice_set, &[1, 1, 1, 4]),
+            mk_states(&graph, &[0b1000])
+        );
+        // zero weights make the variables irrelevant
+        let choice_set = mk_states(&graph, &[0b0011, 0b0100]);
+        assert_eq!(
+            pivot.ham_furthest_within_weighted(&graph, &choice_set, &[0, 0, 1, 0]),
+            mk_states(&graph, &[0b0100])
+        );
+    }
+
+    #[test]
+    fn test_out_degree_weights() {
+        let bool_network = BooleanNetwork::try_from(
+            r#"
+            A -> B
+            A -> C
+            B -> C
+            $B: A
+            $C: A & B
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(out_degree_weights(bool_network.as_graph()), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_ham_furthest_within_deep_bdd() {
+        // none of the computations recurses along the BDDs; a small stack is enough
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(deep_bdd_computations)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    fn deep_bdd_computations() {
+        // a long chain of variables, each only copying itself
+        let num_vars = 2_001;
+        let names = (0..num_vars).map(|i| format!("v_{i}")).collect::<Vec<_>>();
+        let mut graph = RegulatoryGraph::new(names.clone());
+        for name in &names {
+            graph.add_regulation(name, name, true, None).unwrap();
+        }
+        let mut bool_network = BooleanNetwork::new(graph);
+        for var in bool_network.variables().collect::<Vec<_>>() {
+            bool_network
+                .add_update_function(var, FnUpdate::mk_var(var))
+                .unwrap();
+        }
+        let context = SymbolicContext::new(&bool_network).unwrap();
+        let vars = bool_network.variables().collect::<Vec<_>>();
+
+        // the two alternating states; the one starting with `true` has more `true` values
+        let alternating = vars
+            .windows(2)
+            .map(|pair| {
+                context
+                    .mk_state_variable_is_true(pair[0])
+                    .xor(&context.mk_state_variable_is_true(pair[1]))
+            })
+            .fold(context.mk_constant(true), |acc, it| acc.and(&it));
+        let choice_set = GraphColoredVertices::new(alternating, &context);
+        let all_false = context
+            .state_variables()
+            .iter()
+            .map(|var| (*var, false))
+            .collect::<Vec<_>>();
+        let pivot =
+            GraphColoredVertices::new(context.mk_constant(true).select(&all_false), &context);
+
+        let furthest = pivot.ham_furthest_within(&choice_set);
+        assert_eq!(furthest, choice_set.fix_network_variable(vars[0], true));
+
+        let graph = SymbolicAsyncGraph::new(&bool_network).unwrap();
+        let closest = choice_set.fix_network_variable(vars[0], false);
+        assert_eq!(hamming_distance(&graph, &pivot, &choice_set), Some(1_000));
+        assert_eq!(closest_within(&graph, &pivot, &choice_set), closest);
+    }
+
+    #[test]
+    fn test_ham_furthest_within_colored() {
+        // the parameter does not influence the dynamics, it just creates two colors
+        let bool_network = BooleanNetwork::try_from(
+            r#"
+            A -> A
+            B -> B
+            C -> C
+            $A: A | (A & p)
+            $B: B
+            $C: C
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bool_network).unwrap();
+        assert_eq!(graph.unit_colors().exact_cardinality(), BigUint::from(2u32));
+
+        let p = bool_network.find_parameter("p").unwrap();
+        let p_true = graph.unit_colors().copy(
+            graph
+                .symbolic_context()
+                .mk_uninterpreted_function_is_true(p, &[]),
+        );
+        let p_false = graph.unit_colors().minus(&p_true);
+
+        let in_color =
+            |states: &[usize], colors| mk_states(&graph, states).intersect_colors(colors);
+
+        let pivot = in_color(&[0b000], &p_false).union(&in_color(&[0b110], &p_true));
+        let unit = graph.unit_colored_vertices();
+
+        let expected = in_color(&[0b111], &p_false).union(&in_color(&[0b001], &p_true));
+        assert_eq!(pivot.ham_furthest_within_colored(&graph, unit), expected);
+
+        // a pivot set instead of a single pivot, and a color missing in the choice set
+        let pivot = in_color(&[0b000, 0b001], &p_false).union(&in_color(&[0b110], &p_true));
+        let choice_set = in_color(&[0b011, 0b110], &p_false);
+        assert_eq!(
+            furthest_within_per_color(&graph, &pivot, &choice_set),
+            in_color(&[0b110], &p_false)
+        );
+
+        // every choice state is within distance 1, the pivot itself is not the furthest,
+        // unless it is the only choice
+        let pivot = in_color(&[0b000], &p_false).union(&in_color(&[0b000], &p_true));
+        let choice_set = in_color(&[0b000, 0b010], &p_false).union(&in_color(&[0b000], &p_true));
+        assert_eq!(
+            furthest_within_per_color(&graph, &pivot, &choice_set),
+            in_color(&[0b010], &p_false).union(&in_color(&[0b000], &p_true))
+        );
+
+        // colors are never mixed up
+        let pivot = in_color(&[0b000], &p_false);
+        let choice_set = in_color(&[0b001], &p_false).union(&in_color(&[0b111], &p_true));
+        assert_eq!(
+            furthest_within_per_color(&graph, &pivot, &choice_set),
+            in_color(&[0b001], &p_false)
+        );
+    }
+
     #[test]
     fn test_unit_set() {
         let async_graph = basic_async_graph();
@@ -310,4 +835,182 @@ mod tests {
 
         println!("{valuations:?}");
     }
+
+    fn four_variable_graph() -> SymbolicAsyncGraph {
+        let bool_network = BooleanNetwork::try_from(
+            r#"
+            A -> A
+            B -> B
+            C -> C
+            D -> D
+            $A: A
+            $B: B
+            $C: C
+            $D: D
+            "#,
+        )
+        .unwrap();
+        SymbolicAsyncGraph::new(&bool_network).unwrap()
+    }
+
+    /// The set of states given by their indices (the `i`-th bit is the `i`-th variable).
+    fn mk_states(graph: &SymbolicAsyncGraph, states: &[usize]) -> GraphColoredVertices {
+        let variables = graph.variables().collect::<Vec<_>>();
+        states
+            .iter()
+            .map(|state| {
+                let valuation = variables
+                    .iter()
+                    .enumerate()
+                    .map(|(bit, var)| (*var, state & (1 << bit) != 0))
+                    .collect::<Vec<_>>();
+                graph.mk_subspace(&valuation)
+            })
+            .fold(graph.mk_empty_colored_vertices(), |acc, it| acc.union(&it))
+    }
+
+    fn states_of(set: &GraphColoredVertices) -> Vec<ArrayBitVector> {
+        set.vertices().materialize().iter().collect()
+    }
+
+    fn brute_force_distance(state: &ArrayBitVector, set: &GraphColoredVertices) -> usize {
+        states_of(set)
+            .iter()
+            .map(|other| {
+                (0..state.len())
+                    .filter(|i| state.get(*i) != other.get(*i))
+                    .count()
+            })
+            .min()
+            .unwrap()
+    }
+
+    fn state_index(state: &ArrayBitVector) -> usize {
+        (0..state.len())
+            .filter(|i| state.get(*i))
+            .map(|i| 1 << i)
+            .sum()
+    }
+
+    #[test]
+    fn test_hamming_ball_and_sphere() {
+        let graph = four_variable_graph();
+        let pivot = mk_states(&graph, &[0b0000]);
+
+        // there are `4 choose k` states at distance `k` from a single state
+        for (k, expected) in [1u32, 4, 6, 4, 1].into_iter().enumerate() {
+            let sphere = hamming_sphere(&graph, &pivot, k);
+            assert_eq!(sphere.exact_cardinality(), BigUint::from(expected));
+        }
+        assert!(hamming_sphere(&graph, &pivot, 5).is_empty());
+        assert_eq!(
+            hamming_ball(&graph, &pivot, 2).exact_cardinality(),
+            BigUint::from(11u32)
+        );
+        assert_eq!(
+            &hamming_ball(&graph, &pivot, 4),
+            graph.unit_colored_vertices()
+        );
+
+        let pivot = mk_states(&graph, &[0b0000, 0b0111]);
+        let sphere = hamming_sphere(&graph, &pivot, 2);
+        for state in states_of(graph.unit_colored_vertices()) {
+            let in_sphere = brute_force_distance(&state, &pivot) == 2;
+            assert_eq!(
+                in_sphere,
+                !mk_states(&graph, &[state_index(&state)])
+                    .intersect(&sphere)
+                    .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_hamming_ball_brute_force() {
+        let graph = four_variable_graph();
+        let sets = [
+            mk_states(&graph, &[0b0101]),
+            mk_states(&graph, &[0b0000, 0b1111]),
+            mk_states(&graph, &[0b0001, 0b0011, 0b1000]),
+            // the last two variables are free
+            mk_states(&graph, &[0b0001, 0b0101, 0b1001, 0b1101]),
+        ];
+
+        for set in sets {
+            for k in 0..=4 {
+                let ball = hamming_ball(&graph, &set, k);
+                for state in states_of(graph.unit_colored_vertices()) {
+                    let in_ball = !mk_states(&graph, &[state_index(&state)])
+                        .intersect(&ball)
+                        .is_empty();
+                    assert_eq!(in_ball, brute_force_distance(&state, &set) <= k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let graph = four_variable_graph();
+
+        let set_a = mk_states(&graph, &[0b0000, 0b1100]);
+        let set_b = mk_states(&graph, &[0b1111, 0b0011]);
+        assert_eq!(hamming_distance(&graph, &set_a, &set_b), Some(2));
+        assert_eq!(hamming_distance(&graph, &set_a, &set_a), Some(0));
+
+        let set_c = mk_states(&graph, &[0b0111, 0b1110]);
+        assert_eq!(hamming_distance(&graph, &set_a, &set_c), Some(1));
+
+        let empty = graph.mk_empty_colored_vertices();
+        assert_eq!(hamming_distance(&graph, &set_a, &empty), None);
+    }
+
+    #[test]
+    fn test_closest_and_furthest_within() {
+        let graph = four_variable_graph();
+
+        let pivot = mk_states(&graph, &[0b0000, 0b0011]);
+        let choice_sets = [
+            mk_states(&graph, &[0b1111, 0b1100, 0b0111]),
+            mk_states(&graph, &[0b1000, 0b1110, 0b0101]),
+            graph.unit_colored_vertices().clone(),
+        ];
+
+        for choice_set in choice_sets {
+            let distances = states_of(&choice_set)
+                .iter()
+                .map(|state| brute_force_distance(state, &pivot))
+                .collect::<Vec<_>>();
+
+            let closest = closest_within(&graph, &pivot, &choice_set);
+            assert!(closest.is_singleton() && closest.is_subset(&choice_set));
+            assert_eq!(
+                brute_force_distance(&states_of(&closest)[0], &pivot),
+                *distances.iter().min().unwrap()
+            );
+
+            let furthest = furthest_within(&graph, &pivot, &choice_set);
+            assert!(furthest.is_singleton() && furthest.is_subset(&choice_set));
+            assert_eq!(
+                brute_force_distance(&states_of(&furthest)[0], &pivot),
+                *distances.iter().max().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_furthest_within_skips_pivot() {
+        let graph = four_variable_graph();
+        let pivot = mk_states(&graph, &[0b0000]);
+
+        // every choice state is within distance 1, the pivot itself is not the furthest
+        let choice_set = mk_states(&graph, &[0b0000, 0b0001]);
+        assert_eq!(
+            furthest_within(&graph, &pivot, &choice_set),
+            mk_states(&graph, &[0b0001])
+        );
+
+        // the pivot is the only choice
+        assert_eq!(furthest_within(&graph, &pivot, &pivot), pivot);
+    }
 }
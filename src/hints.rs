@@ -0,0 +1,137 @@
+//! Pivot hints for the subproblems of the decomposition.
+//!
+//! After the SCC of a pivot is found, the decomposition continues with the rest of the
+//! forward-reachable states and with the states that are not forward-reachable. A good pivot
+//! for the former is a state discovered *last* by the forward reachability (the last BFS
+//! layer, or the last saturation step), and for the latter a direct predecessor of the SCC.
+//!
+//! The restrictor (trimming) may remove all of these states from the next subproblem. In such
+//! case, the hint moves backwards from the removed states to the closest remaining ones,
+//! instead of giving up and picking the pivot from the whole subproblem.
+
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+
+use crate::transition_system::SymbolicTransitionSystem;
+
+/// The hint for the rest of the forward-reachable states: the `frontier` (the states
+/// discovered last by the forward reachability) within the `fwd_remaining` states, or
+/// the remaining states closest to it (see [closest_backward]).
+///
+/// The search only goes through the forward-reachable states outside of the SCC of the
+/// pivot (`fwd_outside_scc`, before trimming): within the forward-reachable states, all the
+/// predecessors of the SCC are in the SCC again, so no remaining state is found through it.
+pub(crate) fn fwd_hint<G: SymbolicTransitionSystem>(
+    graph: &G,
+    frontier: &GraphColoredVertices,
+    fwd_outside_scc: &GraphColoredVertices,
+    fwd_remaining: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    let found = frontier.intersect(fwd_remaining);
+    if !found.is_empty() {
+        return found;
+    }
+    let initial = frontier.intersect(fwd_outside_scc);
+    closest_backward(graph, &initial, fwd_remaining, fwd_outside_scc)
+}
+
+/// The hint for the states of the `restriction` that are not forward-reachable: the
+/// predecessors of `the_scc` within the `rest_remaining` states, or the remaining states
+/// closest to them (see [closest_backward]).
+pub(crate) fn rest_hint<G: SymbolicTransitionSystem>(
+    graph: &G,
+    the_scc: &GraphColoredVertices,
+    restriction: &GraphColoredVertices,
+    rest_remaining: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    // `the_scc` itself is never remaining, so the first nonempty candidate are
+    // its (proper) predecessors
+    closest_backward(graph, the_scc, rest_remaining, restriction)
+}
+
+/// The states of `target` that are backward-reachable from `initial` within the `set` in the
+/// fewest steps, or an empty set if there are none (then the pivot is picked from
+/// the whole subproblem).
+fn closest_backward<G: SymbolicTransitionSystem>(
+    graph: &G,
+    initial: &GraphColoredVertices,
+    target: &GraphColoredVertices,
+    set: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    let mut visited = initial.clone();
+    let mut current_layer = initial.clone();
+    loop {
+        let found = current_layer.intersect(target);
+        if !found.is_empty() {
+            break found;
+        }
+
+        current_layer = graph.pre_within(&current_layer, set).minus(&visited);
+        if current_layer.is_empty() {
+            break current_layer;
+        }
+        visited = visited.union(&current_layer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+    #[test]
+    fn hints_fall_back_to_closest_states() {
+        // a counter 00 -> 10 -> 11 -> 01 (and 01 is a fixed point)
+        let bn = BooleanNetwork::try_from(
+            r#"
+            B -| A
+            A -> B
+            B -> B
+            $A: !B
+            $B: A | B
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let state = |a, b| graph.mk_subspace(&[(var_a, a), (var_b, b)]);
+        let unit = graph.unit_colored_vertices();
+
+        // the last layer is kept if possible
+        let frontier = state(false, true);
+        assert_eq!(fwd_hint(&graph, &frontier, unit, unit), frontier);
+
+        // the last layer was removed -> its closest predecessors
+        let remaining = unit.minus(&frontier);
+        assert_eq!(
+            fwd_hint(&graph, &frontier, unit, &remaining),
+            state(true, true)
+        );
+        let remaining = state(false, false).union(&state(true, false));
+        assert_eq!(
+            fwd_hint(&graph, &frontier, unit, &remaining),
+            state(true, false)
+        );
+
+        // the SCC is never searched through
+        let outside_scc = unit.minus(&state(true, true));
+        assert!(fwd_hint(&graph, &frontier, &outside_scc, &remaining).is_empty());
+        assert!(fwd_hint(&graph, &state(true, true), &outside_scc, &remaining).is_empty());
+
+        // the direct predecessors of the SCC, or the closest ones
+        let the_scc = state(false, true);
+        let rest = unit.minus(&the_scc);
+        assert_eq!(rest_hint(&graph, &the_scc, unit, &rest), state(true, true));
+        assert_eq!(
+            rest_hint(&graph, &the_scc, unit, &state(false, false)),
+            state(false, false)
+        );
+
+        // nothing is backward-reachable -> no hint
+        let the_scc = state(false, false);
+        let rest = unit.minus(&the_scc);
+        assert!(rest_hint(&graph, &the_scc, unit, &rest).is_empty());
+    }
+}
@@ -0,0 +1,406 @@
+//! Decomposition of networks with *input variables*.
+//!
+//! An input variable has an identity update function (`$X: X`), hence its value
+//! never changes and the state space falls apart into `2^inputs` disjoint
+//! subspaces - one for each valuation of the inputs. Instead of decomposing the
+//! whole space at once (which yields the union of the SCCs of all the
+//! subspaces), we enumerate the input valuations and decompose each input-fixed
+//! subspace separately, so that the SCCs can be reported per input combination.
+
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+
+use crate::assert_precondition_graph_not_colored;
+use crate::chain::Config;
+use crate::chain::chain;
+use crate::transition_system::SymbolicTransitionSystem;
+
+/// A valuation of the input variables; one `(variable, value)` pair per input.
+pub type InputValuation = Vec<(VariableId, bool)>;
+
+/// Iterator over all the valuations of the given input variables.
+///
+/// The valuations are enumerated in the order of a binary counter, the first
+/// input being the least significant "bit".
+pub struct InputValuations {
+    inputs: Vec<VariableId>,
+    next: Option<Vec<bool>>,
+}
+
+impl InputValuations {
+    pub fn new(inputs: &[VariableId]) -> Self {
+        InputValuations {
+            inputs: inputs.to_vec(),
+            next: Some(vec![false; inputs.len()]),
+        }
+    }
+}
+
+impl Iterator for InputValuations {
+    type Item = InputValuation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+
+        let mut successor = current.clone();
+        // binary increment; overflow (all `true` before) ends the enumeration
+        if let Some(first_false) = successor.iter().position(|it| !it) {
+            successor[..first_false].fill(false);
+            successor[first_false] = true;
+            self.next = Some(successor);
+        }
+
+        Some(self.inputs.iter().copied().zip(current).collect())
+    }
+}
+
+/// Iterator over the valuations of the given input variables that occur in a set of
+/// states, together with the subspace of the set where the inputs are fixed to them.
+///
+/// The set is split on one input at a time and the empty halves are skipped, so the
+/// valuations that do not occur are never built. The valuations are enumerated in the
+/// same order as by [InputValuations].
+struct InputSubspaces {
+    inputs: Vec<VariableId>,
+    /// The subspaces that remain to be split, each with the values of the last inputs
+    /// (in reverse order) that are already fixed in it.
+    stack: Vec<(Vec<bool>, GraphColoredVertices)>,
+}
+
+impl InputSubspaces {
+    fn new(inputs: &[VariableId], set: &GraphColoredVertices) -> Self {
+        let stack = if set.is_empty() {
+            Vec::new()
+        } else {
+            vec![(Vec::new(), set.clone())]
+        };
+        InputSubspaces {
+            inputs: inputs.to_vec(),
+            stack,
+        }
+    }
+}
+
+impl Iterator for InputSubspaces {
+    type Item = (InputValuation, GraphColoredVertices);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((values, subspace)) = self.stack.pop() {
+            if values.len() == self.inputs.len() {
+                let values = values.into_iter().rev();
+                return Some((self.inputs.iter().copied().zip(values).collect(), subspace));
+            }
+
+            // the last input is the most significant "bit", so it is split first, and
+            // the `true` half is pushed first, so that the `false` half is popped first
+            let input = self.inputs[self.inputs.len() - values.len() - 1];
+            for value in [true, false] {
+                let half = subspace.fix_network_variable(input, value);
+                if !half.is_empty() {
+                    let mut values = values.clone();
+                    values.push(value);
+                    self.stack.push((values, half));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Decomposes `graph` into SCCs separately for every valuation of the `inputs`.
+///
+/// Yields the input valuation together with the SCCs found in the subspace where
+/// the inputs are fixed to said valuation. The subspaces are decomposed lazily,
+/// one per call to `next`, using [chain] configured by `config`.
+///
+/// The `inputs` must be invariant in the graph (no transition changes their
+/// value), otherwise an SCC could span several input-fixed subspaces.
+///
+/// Only the valuations that occur in the states of the `graph` are yielded, in the
+/// order of [InputValuations]. The states are split on one input at a time, so a
+/// restricted `graph` with many inputs does not cost `2^inputs.len()` valuations.
+pub fn chain_per_input<G: SymbolicTransitionSystem>(
+    graph: G,
+    inputs: &[VariableId],
+    config: Config,
+) -> impl Iterator<Item = (InputValuation, Vec<GraphColoredVertices>)> + use<G> {
+    assert_precondition_graph_not_colored(&graph);
+    assert_precondition_inputs_invariant(&graph, inputs);
+
+    InputSubspaces::new(inputs, graph.unit_colored_vertices()).map(move |(valuation, subspace)| {
+        let sccs = chain(graph.restrict(&subspace), config.clone()).collect();
+
+        (valuation, sccs)
+    })
+}
+
+/// The variables that no transition of the `graph` changes and that are not fixed
+/// in the states of the `graph`.
+///
+/// Typically, these are the inputs with an identity update function, but not only;
+/// e.g. in a subsystem, every change of a variable can be blocked. The states of the
+/// `graph` fall apart into disconnected subspaces, one per valuation of these
+/// variables (see [chain_per_input] and [crate::chain::Config::split_invariant]).
+pub fn invariant_variables<G: SymbolicTransitionSystem>(graph: &G) -> Vec<VariableId> {
+    let unit = graph.unit_colored_vertices();
+    graph
+        .variables()
+        .into_iter()
+        .filter(|var| {
+            [false, true].into_iter().all(|value| {
+                let half = unit.fix_network_variable(*var, value);
+                !half.is_empty() && graph.var_post_out(*var, &half).is_empty()
+            })
+        })
+        .collect()
+}
+
+/// Assert that no transition of the given graph changes any of the `inputs`.
+fn assert_precondition_inputs_invariant<G: SymbolicTransitionSystem>(
+    graph: &G,
+    inputs: &[VariableId],
+) {
+    let unit = graph.unit_colored_vertices();
+    for input in inputs {
+        assert!(
+            [false, true].into_iter().all(|value| {
+                let half = unit.fix_network_variable(*input, value);
+                graph.var_post_out(*input, &half).is_empty()
+            }),
+            "precondition violated; input variable {} is not invariant",
+            graph.symbolic_context().get_network_variable_name(*input)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Strategy;
+    use crate::chain::TrimLvl;
+    use crate::semantics::SymbolicRelationGraph;
+    use crate::test_utils::skip_large_model;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use std::collections::HashSet;
+    use test_generator::test_resources;
+
+    #[test]
+    fn input_valuations_enumeration() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> A
+            B -> B
+            $A: A
+            $B: B
+            "#,
+        )
+        .unwrap();
+        let inputs = bn.inputs(true);
+        let (var_a, var_b) = (inputs[0], inputs[1]);
+
+        let valuations = InputValuations::new(&inputs).collect::<Vec<_>>();
+
+        assert_eq!(
+            valuations,
+            vec![
+                vec![(var_a, false), (var_b, false)],
+                vec![(var_a, true), (var_b, false)],
+                vec![(var_a, false), (var_b, true)],
+                vec![(var_a, true), (var_b, true)],
+            ]
+        );
+
+        // the empty valuation is the only valuation of no inputs
+        assert_eq!(
+            InputValuations::new(&[]).collect::<Vec<_>>(),
+            vec![Vec::new()]
+        );
+    }
+
+    #[test]
+    fn chain_per_input_basic() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            I -> I
+            I -> A
+            A -| A
+            $I: I
+            $A: I & !A
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_i = bn.as_graph().find_variable("I").unwrap();
+
+        let grouped =
+            chain_per_input(graph.clone(), &[var_i], Config::default()).collect::<Vec<_>>();
+
+        assert_eq!(grouped.len(), 2);
+
+        // I=false: A goes to false and stays there -> no non-trivial SCC
+        assert_eq!(grouped[0].0, vec![(var_i, false)]);
+        assert!(grouped[0].1.is_empty());
+
+        // I=true: A oscillates
+        assert_eq!(grouped[1].0, vec![(var_i, true)]);
+        assert_eq!(grouped[1].1, vec![graph.mk_subspace(&[(var_i, true)])]);
+    }
+
+    #[test]
+    fn chain_per_input_restricted() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> A
+            B -> B
+            C -> C
+            $A: A
+            $B: B
+            $C: C
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let inputs = bn.inputs(true);
+        let (var_a, var_b, var_c) = (inputs[0], inputs[1], inputs[2]);
+
+        let states = graph
+            .mk_subspace(&[(var_a, true)])
+            .union(&graph.mk_subspace(&[(var_b, false), (var_c, true)]));
+        let restricted = graph.restrict(&states);
+
+        // only the valuations that occur, in the order of the binary counter
+        let expected = InputValuations::new(&inputs)
+            .filter(|valuation| !graph.mk_subspace(valuation).intersect(&states).is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(expected.len(), 5);
+        let valuations = chain_per_input(restricted, &inputs, Config::default())
+            .map(|(valuation, _)| valuation)
+            .collect::<Vec<_>>();
+        assert_eq!(valuations, expected);
+
+        // a single state of many inputs is a single valuation
+        let names = (0..40).map(|i| format!("I{i}")).collect::<Vec<_>>();
+        let model = names
+            .iter()
+            .map(|name| format!("{name} -> {name}\n${name}: {name}\n"))
+            .collect::<String>();
+        let bn = BooleanNetwork::try_from(model.as_str()).unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let inputs = bn.inputs(true);
+        let state = inputs.iter().map(|var| (*var, true)).collect::<Vec<_>>();
+        let restricted = graph.restrict(&graph.mk_subspace(&state));
+        let grouped = chain_per_input(restricted, &inputs, Config::default()).collect::<Vec<_>>();
+        assert_eq!(grouped, vec![(state, Vec::new())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "precondition violated")]
+    fn chain_per_input_non_invariant() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -| A
+            $A: !A
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+
+        let _ = chain_per_input(graph, &[var_a], Config::default());
+    }
+
+    #[test_resources("./models/bbm-inputs-identity/*.aeon")]
+    fn compare_chain_per_input_with_chain(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let inputs = bn.inputs(true);
+
+        let config = Config {
+            trim_lvl: TrimLvl::None,
+            strategy: Strategy::Chain,
+            ..Default::default()
+        };
+
+        let whole_space = chain(graph.clone(), config.clone()).collect::<HashSet<_>>();
+        let per_input = chain_per_input(graph, &inputs, config)
+            .flat_map(|(_, sccs)| sccs)
+            .collect::<HashSet<_>>();
+
+        assert_eq!(whole_space, per_input);
+    }
+
+    #[test]
+    fn invariant_variables_detection() {
+        // `A` oscillates while `I` holds, and decays to `false` otherwise
+        let bn = BooleanNetwork::try_from(
+            r#"
+            I -> I
+            I -> A
+            A -| A
+            $I: I
+            $A: I & !A
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_i = bn.as_graph().find_variable("I").unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+
+        assert_eq!(invariant_variables(&graph), vec![var_i]);
+
+        // fixed variables are not reported
+        let restricted = graph.restrict(&graph.mk_subspace(&[(var_i, false)]));
+        assert!(invariant_variables(&restricted).is_empty());
+
+        // in the fixed point, no variable changes
+        let fixed_point = graph.mk_subspace(&[(var_i, false), (var_a, false)]);
+        let with_fixed_point =
+            graph.restrict(&graph.mk_subspace(&[(var_i, true)]).union(&fixed_point));
+        assert_eq!(invariant_variables(&with_fixed_point), vec![var_i]);
+
+        let config = Config {
+            split_invariant: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            chain(graph.clone(), config).collect::<Vec<_>>(),
+            vec![graph.mk_subspace(&[(var_i, true)])]
+        );
+    }
+
+    #[test_resources("./models/bbm-inputs-identity/*.aeon")]
+    fn compare_split_invariant_with_chain(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        let config = Config {
+            split_invariant: true,
+            ..Default::default()
+        };
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let invariant = invariant_variables(&graph);
+        assert!(bn.inputs(true).iter().all(|it| invariant.contains(it)));
+
+        let whole_space = chain(graph.clone(), Config::default()).collect::<HashSet<_>>();
+        let split = chain(graph, config.clone()).collect::<HashSet<_>>();
+        assert_eq!(whole_space, split);
+
+        let graph = SymbolicRelationGraph::synchronous(&bn).unwrap();
+        let whole_space = chain(graph.clone(), Config::default()).collect::<HashSet<_>>();
+        let split = chain(graph, config).collect::<HashSet<_>>();
+        assert_eq!(whole_space, split);
+    }
+}
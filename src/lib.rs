@@ -1,12 +1,32 @@
-use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use crate::transition_system::SymbolicTransitionSystem;
 
+pub mod analysis;
+pub mod basins;
 pub mod chain;
+pub mod checkpoint;
+pub mod dot;
+pub mod explicit;
 pub mod fwd_bwd;
-mod hamming;
+pub mod hamming;
+mod hints;
+pub mod inputs;
+pub mod perturbation;
+pub mod preprocessing;
+pub mod projection;
+#[cfg(test)]
+mod random_network;
+pub mod reachability;
+pub mod sampling;
+pub mod semantics;
+pub mod spilling;
+#[cfg(test)]
+mod test_utils;
+pub mod transition_system;
 mod trimming;
+pub mod witness;
 
 /// Assert that the given graph has no colors.
-fn assert_precondition_graph_not_colored(graph: &SymbolicAsyncGraph) {
+fn assert_precondition_graph_not_colored<G: SymbolicTransitionSystem>(graph: &G) {
     assert_eq!(
         graph.symbolic_context().num_parameter_variables(),
         0,
@@ -0,0 +1,369 @@
+//! Decomposition of networks under *perturbations*: knockouts and over-expressions.
+//!
+//! A perturbed variable is clamped to a constant value from the start and never changes.
+//! In the asynchronous semantics, this is the original graph restricted to the subspace
+//! where the perturbed variables have the fixed values: the transitions leaving the subspace
+//! are exactly the (removed) transitions of the perturbed variables. Since transitions are
+//! only removed, every SCC of the perturbed network is a subset of some original SCC.
+//!
+//! Note that this differs from replacing the update functions by constants: then, the states
+//! outside of the subspace are kept and can still form (transient) SCCs before the perturbed
+//! variables reach their values.
+
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::chain::Config;
+use crate::chain::chain;
+
+/// The perturbed variables with their fixed values: `false` is a knockout, `true` an
+/// over-expression.
+pub type Perturbation = Vec<(VariableId, bool)>;
+
+/// The asynchronous graph of the network with the `perturbation` applied.
+///
+/// The resulting graph only contains the states consistent with the `perturbation`.
+pub fn perturb(
+    graph: &SymbolicAsyncGraph,
+    perturbation: &[(VariableId, bool)],
+) -> SymbolicAsyncGraph {
+    graph.restrict(&graph.mk_subspace(perturbation))
+}
+
+/// Decomposes `graph` with the `perturbation` applied into SCCs, using [chain] configured
+/// by `config`.
+pub fn chain_perturbed(
+    graph: &SymbolicAsyncGraph,
+    perturbation: &[(VariableId, bool)],
+    config: Config,
+) -> Vec<GraphColoredVertices> {
+    chain(perturb(graph, perturbation), config).collect()
+}
+
+/// The difference between the SCCs of the original and of a perturbed network.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SccComparison {
+    /// SCCs present in both networks.
+    pub preserved: Vec<GraphColoredVertices>,
+    /// SCCs of the original network that do not exist in the perturbed one. This includes
+    /// all the SCCs that are not consistent with the perturbation.
+    pub lost: Vec<GraphColoredVertices>,
+    /// SCCs of the perturbed network that do not exist in the original one.
+    pub created: Vec<GraphColoredVertices>,
+}
+
+impl SccComparison {
+    /// Compares two lists of SCCs, keeping the order of the lists.
+    pub fn new(original: &[GraphColoredVertices], perturbed: &[GraphColoredVertices]) -> Self {
+        let original_set = original.iter().collect::<HashSet<_>>();
+        let perturbed_set = perturbed.iter().collect::<HashSet<_>>();
+
+        SccComparison {
+            preserved: perturbed
+                .iter()
+                .filter(|scc| original_set.contains(scc))
+                .cloned()
+                .collect(),
+            lost: original
+                .iter()
+                .filter(|scc| !perturbed_set.contains(scc))
+                .cloned()
+                .collect(),
+            created: perturbed
+                .iter()
+                .filter(|scc| !original_set.contains(scc))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether the perturbation does not change the SCCs at all.
+    pub fn is_unchanged(&self) -> bool {
+        self.lost.is_empty() && self.created.is_empty()
+    }
+}
+
+/// All the single-variable perturbations (a knockout and an over-expression per variable).
+pub fn single_perturbations(variables: &[VariableId]) -> Vec<Perturbation> {
+    variables
+        .iter()
+        .flat_map(|var| [vec![(*var, false)], vec![(*var, true)]])
+        .collect()
+}
+
+/// All the perturbations of two different variables (four per pair of variables).
+pub fn double_perturbations(variables: &[VariableId]) -> Vec<Perturbation> {
+    let mut result = Vec::new();
+    for (i, first) in variables.iter().enumerate() {
+        for second in &variables[i + 1..] {
+            for (first_value, second_value) in
+                [(false, false), (false, true), (true, false), (true, true)]
+            {
+                result.push(vec![(*first, first_value), (*second, second_value)]);
+            }
+        }
+    }
+    result
+}
+
+/// Screening of many perturbations of the same network against its original SCCs.
+///
+/// Every SCC of a perturbation is a subset of some SCC of any of its sub-perturbations.
+/// Hence, a perturbation is only decomposed within the SCCs of the perturbation without its
+/// last variable (its *parent*, e.g. a single perturbation for a double one), which is
+/// typically much smaller than the whole perturbed subspace. Only the SCCs of the parents
+/// are cached, as nothing else is ever reused.
+pub struct Screening {
+    graph: SymbolicAsyncGraph,
+    config: Config,
+    original: Vec<GraphColoredVertices>,
+    cache: HashMap<Perturbation, Vec<GraphColoredVertices>>,
+}
+
+impl Screening {
+    /// Decomposes the unperturbed `graph` using [chain] configured by `config` (which is
+    /// then also used for all the perturbations).
+    pub fn new(graph: SymbolicAsyncGraph, config: Config) -> Self {
+        let original = chain(graph.clone(), config.clone()).collect();
+        Screening {
+            graph,
+            config,
+            original,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The SCCs of the unperturbed network.
+    pub fn original(&self) -> &[GraphColoredVertices] {
+        &self.original
+    }
+
+    /// The SCCs of the network with the `perturbation` applied.
+    pub fn decompose(&mut self, perturbation: &[(VariableId, bool)]) -> Vec<GraphColoredVertices> {
+        let Some((_, parent)) = perturbation.split_last() else {
+            return self.original.clone();
+        };
+        if let Some(sccs) = self.cache.get(perturbation) {
+            return sccs.clone();
+        }
+
+        let candidates = self
+            .decompose_parent(parent)
+            .iter()
+            .fold(self.graph.mk_empty_colored_vertices(), |acc, it| {
+                acc.union(it)
+            })
+            .intersect(&self.graph.mk_subspace(perturbation));
+        chain(self.graph.restrict(&candidates), self.config.clone()).collect()
+    }
+
+    /// Same as [Screening::decompose], but the result is cached for the other perturbations
+    /// with the same `parent`.
+    fn decompose_parent(&mut self, parent: &[(VariableId, bool)]) -> Vec<GraphColoredVertices> {
+        if parent.is_empty() {
+            return self.original.clone();
+        }
+        if let Some(sccs) = self.cache.get(parent) {
+            return sccs.clone();
+        }
+
+        let sccs = self.decompose(parent);
+        self.cache.insert(parent.to_vec(), sccs.clone());
+        sccs
+    }
+
+    /// Compares the SCCs of the network with the `perturbation` applied to the original ones.
+    pub fn compare(&mut self, perturbation: &[(VariableId, bool)]) -> SccComparison {
+        let perturbed = self.decompose(perturbation);
+        SccComparison::new(&self.original, &perturbed)
+    }
+
+    /// The perturbations (from `perturbations`) that change the SCCs, with the changes.
+    pub fn screen(&mut self, perturbations: &[Perturbation]) -> Vec<(Perturbation, SccComparison)> {
+        perturbations
+            .iter()
+            .map(|perturbation| (perturbation.clone(), self.compare(perturbation)))
+            .filter(|(_, comparison)| !comparison.is_unchanged())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Strategy;
+    use crate::chain::TrimLvl;
+    use crate::test_utils::arb_network;
+    use crate::test_utils::cycle_network;
+    use crate::test_utils::skip_large_model;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::FnUpdate;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
+    use test_generator::test_resources;
+
+    #[test]
+    fn perturbation_basic() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+
+        let original = chain(graph.clone(), Config::default()).collect::<Vec<_>>();
+        let cycle = graph.mk_subspace(&[(var_c, false)]);
+        assert_eq!(original, vec![cycle.clone()]);
+
+        // knocking out `C` only removes the transient part
+        let perturbed = chain_perturbed(&graph, &[(var_c, false)], Config::default());
+        let comparison = SccComparison::new(&original, &perturbed);
+        assert!(comparison.is_unchanged());
+        assert_eq!(comparison.preserved, vec![cycle.clone()]);
+
+        // over-expressing `C` or knocking out `A` destroys the cycle
+        for perturbation in [vec![(var_c, true)], vec![(var_a, false)]] {
+            let perturbed = chain_perturbed(&graph, &perturbation, Config::default());
+            let comparison = SccComparison::new(&original, &perturbed);
+            assert!(comparison.preserved.is_empty());
+            assert_eq!(comparison.lost, vec![cycle.clone()]);
+            assert!(comparison.created.is_empty());
+        }
+    }
+
+    #[test]
+    fn perturbation_splits_scc() {
+        // `A` oscillates independently of the cycle on `B` and `C`
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -| A
+            C -> B
+            B -| C
+            $A: !A
+            $B: C
+            $C: !B
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+
+        let original = chain(graph.clone(), Config::default()).collect::<Vec<_>>();
+        let perturbed = chain_perturbed(&graph, &[(var_a, true)], Config::default());
+        let comparison = SccComparison::new(&original, &perturbed);
+
+        // only the removed transitions can change the SCCs, hence the original SCC is split
+        assert_eq!(comparison.lost, vec![graph.unit_colored_vertices().clone()]);
+        assert_eq!(
+            comparison.created,
+            vec![graph.mk_subspace(&[(var_a, true)])]
+        );
+    }
+
+    #[test]
+    fn screening_basic() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let variables = bn.variables().collect::<Vec<_>>();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+
+        let singles = single_perturbations(&variables);
+        let doubles = double_perturbations(&variables);
+        assert_eq!(singles.len(), 6);
+        assert_eq!(doubles.len(), 12);
+        assert!(doubles.iter().all(|it| it.len() == 2 && it[0].0 != it[1].0));
+
+        let mut screening = Screening::new(graph.clone(), Config::default());
+        let cycle = graph.mk_subspace(&[(var_c, false)]);
+        assert_eq!(screening.original().to_vec(), vec![cycle.clone()]);
+
+        // only knocking out `C` keeps the cycle
+        let changed = screening.screen(&singles);
+        assert_eq!(changed.len(), 5);
+        for (perturbation, comparison) in changed {
+            assert_ne!(perturbation, vec![(var_c, false)]);
+            assert_eq!(comparison.lost, vec![cycle.clone()]);
+            assert!(comparison.created.is_empty());
+        }
+
+        // every double perturbation destroys the cycle
+        assert_eq!(screening.screen(&doubles).len(), 12);
+
+        // only the parents of the doubles (the singles of `A` and `B`) are cached
+        assert_eq!(screening.cache.len(), 4);
+        assert!(screening.cache.keys().all(|it| it.len() == 1));
+    }
+
+    proptest! {
+        #[test]
+        fn compare_screening_with_chain_perturbed_random(bn in arb_network(5)) {
+            let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+            let variables = bn.variables().collect::<Vec<_>>();
+
+            let mut screening = Screening::new(graph.clone(), Config::default());
+            let perturbations = double_perturbations(&variables)
+                .into_iter()
+                .chain(single_perturbations(&variables));
+            for perturbation in perturbations {
+                let expected = chain_perturbed(&graph, &perturbation, Config::default());
+                prop_assert_eq!(
+                    screening.decompose(&perturbation).into_iter().collect::<HashSet<_>>(),
+                    expected.into_iter().collect::<HashSet<_>>()
+                );
+            }
+        }
+    }
+
+    #[test_resources("./models/bbm-inputs-true/*.aeon")]
+    fn compare_chain_perturbed_with_rebuilt_network(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        if bn.num_vars() < 2 {
+            // (Almost) everything has been inlined; the perturbation below needs two
+            // distinct variables.
+            return;
+        }
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let config = Config {
+            trim_lvl: TrimLvl::None,
+            strategy: Strategy::Chain,
+            ..Default::default()
+        };
+
+        // over-express the first and knock out the last variable
+        let first = bn.variables().next().unwrap();
+        let last = bn.variables().next_back().unwrap();
+        let perturbation = vec![(first, true), (last, false)];
+
+        // the same network with constant update functions (and the regulations adjusted)
+        let mut perturbed_bn = bn.clone();
+        for (var, value) in &perturbation {
+            perturbed_bn
+                .set_update_function(*var, Some(FnUpdate::Const(*value)))
+                .unwrap();
+        }
+        let perturbed_bn = perturbed_bn.infer_valid_graph().unwrap();
+        let perturbed_graph = SymbolicAsyncGraph::new(&perturbed_bn).unwrap();
+        let subspace = perturbed_graph.mk_subspace(&perturbation);
+
+        // only the SCCs where the perturbed variables already have their values
+        let expected = chain(perturbed_graph, config.clone())
+            .filter(|scc| scc.is_subset(&subspace))
+            .map(|scc| scc.into_bdd())
+            .collect::<HashSet<_>>();
+        let perturbed = chain_perturbed(&graph, &perturbation, config)
+            .into_iter()
+            .map(|scc| scc.into_bdd())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(expected, perturbed);
+    }
+}
@@ -0,0 +1,333 @@
+//! Preprocessing of the state space before the decomposition (see
+//! [crate::chain::Preprocessing]).
+//!
+//! Both the percolated trap set and the minimal trap spaces are *trap sets*: no transition
+//! leaves them. Hence every SCC lies either entirely inside or entirely outside of each
+//! of them, and the state space can be partitioned into parts that are decomposed
+//! independently. Moreover, every attractor lies in the percolated trap set and (for
+//! the unrestricted state space) most attractors lie in some minimal trap space.
+
+use crate::assert_precondition_graph_not_colored;
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use biodivine_lib_param_bn::trap_spaces::SymbolicSpaceContext;
+use biodivine_lib_param_bn::trap_spaces::TrapSpaces;
+
+/// The trap set obtained by repeatedly fixing the variables that are forced to a value
+/// (e.g. the constant inputs and the variables they determine).
+///
+/// Within the current trap set `T`, a variable `v` is forced to the value `b` if no
+/// transition leaves `T ∩ {v = b}` and every state of `T ∩ {v = !b}` has a transition
+/// into `T ∩ {v = b}`. Then `T ∩ {v = b}` is again a trap set and the removed states
+/// cannot belong to an attractor. Works for any [SymbolicTransitionSystem].
+pub fn percolate<G: SymbolicTransitionSystem>(graph: &G) -> GraphColoredVertices {
+    let mut trap = graph.unit_colored_vertices().clone();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for var in graph.variables() {
+            for value in [false, true] {
+                let kept = trap.fix_network_variable(var, value);
+                let removed = trap.minus(&kept);
+                if removed.is_empty() {
+                    // already fixed
+                    break;
+                }
+
+                if graph.var_post_out(var, &kept).is_empty()
+                    && removed.is_subset(&graph.var_pre_out(var, &kept))
+                {
+                    trap = kept;
+                    changed = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    trap
+}
+
+/// The minimal trap spaces of the network of the `graph`, or `None` if the network is not
+/// known, or if the `graph` is restricted to a subset of its states (e.g. by a perturbation,
+/// see [crate::perturbation::perturb]). The subsystem has other trap spaces than the network:
+/// in a perturbed subspace, the perturbed variables are fixed even if their update functions
+/// would change them.
+///
+/// The minimal trap spaces are pairwise disjoint. Note that there may be exponentially
+/// many of them (e.g. one per valuation of the free inputs).
+pub fn minimal_trap_spaces<G: SymbolicTransitionSystem>(
+    graph: &G,
+) -> Option<Vec<GraphColoredVertices>> {
+    assert_precondition_graph_not_colored(graph);
+
+    let network = graph.as_network()?;
+    if !graph.unit_colored_vertices().as_bdd().is_true() {
+        return None;
+    }
+
+    let context = SymbolicSpaceContext::new(network);
+    let space_graph = SymbolicAsyncGraph::with_space_context(network, &context)
+        .expect("the network has already been used to build the graph");
+    let minimal = TrapSpaces::minimal_symbolic(
+        &context,
+        &space_graph,
+        &context.mk_unit_colored_spaces(&space_graph),
+        None,
+    );
+
+    let unit = graph.unit_colored_vertices();
+    let spaces = minimal
+        .spaces()
+        .iter()
+        .map(|space| {
+            space
+                .to_values()
+                .into_iter()
+                .fold(unit.clone(), |acc, (var, value)| {
+                    acc.fix_network_variable(var, value)
+                })
+        })
+        .collect();
+    Some(spaces)
+}
+
+/// Partitions the states of the `graph` into (nonempty) parts such that every SCC lies
+/// entirely within one of them.
+///
+/// The parts are the minimal trap spaces within the percolated trap set, the rest of the
+/// percolated trap set and the states outside of it.
+pub fn partition<G: SymbolicTransitionSystem>(graph: &G) -> Vec<GraphColoredVertices> {
+    let percolated = percolate(graph);
+
+    let mut parts = Vec::new();
+    let mut rest = percolated.clone();
+    for space in minimal_trap_spaces(graph).unwrap_or_default() {
+        let part = space.intersect(&percolated);
+        rest = rest.minus(&part);
+        parts.push(part);
+    }
+    parts.push(rest);
+    parts.push(graph.unit_colored_vertices().minus(&percolated));
+
+    parts.retain(|it| !it.is_empty());
+    parts
+}
+
+/// The (nonempty) parts of the states of the `graph` where the attractors are searched
+/// for: the minimal trap spaces within the percolated trap set, or the whole percolated
+/// trap set if the network of the `graph` is not known.
+///
+/// Every minimal trap space contains an attractor, but an attractor need not lie in
+/// a minimal trap space (a *motif-avoidant* attractor); such attractors are missed.
+pub fn attractor_parts<G: SymbolicTransitionSystem>(graph: &G) -> Vec<GraphColoredVertices> {
+    let percolated = percolate(graph);
+    match minimal_trap_spaces(graph) {
+        Some(spaces) => spaces
+            .into_iter()
+            .map(|space| space.intersect(&percolated))
+            .filter(|it| !it.is_empty())
+            .collect(),
+        None => match percolated.is_empty() {
+            true => Vec::new(),
+            false => vec![percolated],
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::SccClassification;
+    use crate::chain::Config;
+    use crate::chain::Preprocessing;
+    use crate::chain::chain;
+    use crate::perturbation::chain_perturbed;
+    use crate::perturbation::perturb;
+    use crate::semantics::SymbolicRelationGraph;
+    use crate::test_utils::arb_network;
+    use crate::test_utils::skip_large_model;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use proptest::prop_assert;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
+    use std::collections::HashSet;
+    use test_generator::test_resources;
+
+    /// `A` is constant, `B` copies `A` and `C` is a free input.
+    fn percolation_network() -> BooleanNetwork {
+        BooleanNetwork::try_from(
+            r#"
+            A -> B
+            C -> C
+            $A: true
+            $B: A
+            $C: C
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn percolation_basic() {
+        let bn = percolation_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+
+        let percolated = graph.mk_subspace(&[(var_a, true), (var_b, true)]);
+        assert_eq!(percolate(&graph), percolated);
+
+        let spaces = minimal_trap_spaces(&graph).unwrap();
+        assert_eq!(spaces.len(), 2);
+        assert!(spaces.contains(&percolated.fix_network_variable(var_c, false)));
+        assert!(spaces.contains(&percolated.fix_network_variable(var_c, true)));
+
+        let parts = partition(&graph);
+        assert_eq!(parts.len(), 3);
+        assert!(parts.contains(&graph.unit_colored_vertices().minus(&percolated)));
+        assert_eq!(attractor_parts(&graph), spaces);
+
+        // the same trap sets work for the synchronous semantics
+        let sync_graph = SymbolicRelationGraph::synchronous(&bn).unwrap();
+        let percolated = sync_graph
+            .unit_colored_vertices()
+            .fix_network_variable(var_a, true)
+            .fix_network_variable(var_b, true);
+        assert_eq!(percolate(&sync_graph), percolated);
+        assert_eq!(minimal_trap_spaces(&sync_graph).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn preprocessing_without_network() {
+        let context = SymbolicRelationGraph::mk_context(&["A", "B"]).unwrap();
+        let a = context.mk_state_variable_is_true(context.find_network_variable("A").unwrap());
+        let b = context.mk_state_variable_is_true(context.find_network_variable("B").unwrap());
+        let a_primed =
+            context.mk_extra_state_variable_is_true(context.find_network_variable("A").unwrap(), 0);
+        let b_primed =
+            context.mk_extra_state_variable_is_true(context.find_network_variable("B").unwrap(), 0);
+
+        // `A` is switched on (keeping `B`), after which `B` can toggle
+        let switch_on = a.not().and(&a_primed).and(&b.iff(&b_primed));
+        let toggle = a.and(&a_primed).and(&b.xor(&b_primed));
+        let unit = context.mk_constant(true);
+        let graph = SymbolicRelationGraph::new(context, unit, switch_on.or(&toggle)).unwrap();
+
+        assert!(minimal_trap_spaces(&graph).is_none());
+        let percolated = percolate(&graph);
+        assert_eq!(percolated.as_bdd(), &a);
+        assert_eq!(attractor_parts(&graph), vec![percolated.clone()]);
+        assert_eq!(partition(&graph).len(), 2);
+
+        let config = Config {
+            preprocessing: Preprocessing::MinimalTrapSpaces,
+            ..Default::default()
+        };
+        assert_eq!(chain(graph, config).collect::<Vec<_>>(), vec![percolated]);
+    }
+
+    #[test]
+    fn preprocessing_perturbed() {
+        // without the perturbation, `X` is switched off and `Y` stays off
+        let bn = BooleanNetwork::try_from(
+            r#"
+            X -> Y
+            Y -| Y
+            $X: false
+            $Y: X & !Y
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_x = bn.as_graph().find_variable("X").unwrap();
+        assert!(minimal_trap_spaces(&graph).is_some());
+
+        // with `X` fixed on, `Y` oscillates
+        let perturbed = perturb(&graph, &[(var_x, true)]);
+        assert!(minimal_trap_spaces(&perturbed).is_none());
+        let oscillation = perturbed.unit_colored_vertices().clone();
+        assert_eq!(attractor_parts(&perturbed), vec![oscillation.clone()]);
+
+        let config = Config {
+            preprocessing: Preprocessing::MinimalTrapSpaces,
+            ..Default::default()
+        };
+        assert_eq!(
+            chain_perturbed(&graph, &[(var_x, true)], config),
+            vec![oscillation]
+        );
+    }
+
+    #[test_resources("./models/bbm-inputs-identity/*.aeon")]
+    fn compare_preprocessing_with_chain(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let expected = chain(graph.clone(), Config::default()).collect::<HashSet<_>>();
+
+        let config = Config {
+            preprocessing: Preprocessing::Partition,
+            ..Default::default()
+        };
+        let partitioned = chain(graph.clone(), config).collect::<HashSet<_>>();
+        assert_eq!(partitioned, expected);
+
+        // every (non-trivial) attractor within a minimal trap space is found
+        let parts = attractor_parts(&graph);
+        let config = Config {
+            preprocessing: Preprocessing::MinimalTrapSpaces,
+            ..Default::default()
+        };
+        let attractors = chain(graph.clone(), config).collect::<HashSet<_>>();
+        assert!(attractors.is_subset(&expected));
+        for scc in &expected {
+            if SccClassification::new(&graph, scc).terminal
+                && parts.iter().any(|part| scc.is_subset(part))
+            {
+                assert!(attractors.contains(scc));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn compare_partition_with_chain_random(bn in arb_network(6)) {
+            let config = Config {
+                preprocessing: Preprocessing::Partition,
+                ..Default::default()
+            };
+
+            let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+            let expected = chain(graph.clone(), Config::default()).collect::<HashSet<_>>();
+            let partitioned = chain(graph.clone(), config.clone()).collect::<HashSet<_>>();
+            prop_assert_eq!(partitioned, expected.clone());
+
+            // the attractors are never removed by the percolation
+            let percolated = percolate(&graph);
+            let sync_graph = SymbolicRelationGraph::synchronous(&bn).unwrap();
+            let sync_percolated = percolate(&sync_graph);
+            for scc in chain(sync_graph.clone(), Config::default()) {
+                if SccClassification::new(&sync_graph, &scc).terminal {
+                    prop_assert!(scc.is_subset(&sync_percolated));
+                }
+            }
+            for scc in expected {
+                if SccClassification::new(&graph, &scc).terminal {
+                    prop_assert!(scc.is_subset(&percolated));
+                }
+            }
+
+            let expected = chain(sync_graph.clone(), Config::default()).collect::<HashSet<_>>();
+            prop_assert_eq!(chain(sync_graph, config).collect::<HashSet<_>>(), expected);
+        }
+    }
+}
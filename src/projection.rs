@@ -0,0 +1,155 @@
+//! Projection of decomposition results onto a subset of "readout" variables (e.g. phenotype
+//! markers), merging the SCCs that cannot be distinguished by the readouts.
+
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// A set of readout values shared by one or more SCCs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Projection {
+    readouts: Vec<VariableId>,
+    /// The readout variables are constrained as in the SCCs, all the other state variables
+    /// are unconstrained.
+    pub values: GraphColoredVertices,
+    /// Indices of the projected SCCs.
+    pub sccs: Vec<usize>,
+}
+
+impl Projection {
+    pub fn readouts(&self) -> &[VariableId] {
+        &self.readouts
+    }
+
+    /// The (sorted) valuations of the readout variables (in the order of `readouts`).
+    pub fn valuations(&self) -> Vec<Vec<bool>> {
+        let mut result = Vec::new();
+        let mut remaining = self.values.clone();
+        while !remaining.is_empty() {
+            let state = remaining.pick_singleton();
+            let valuation = self
+                .readouts
+                .iter()
+                .map(|var| !state.fix_network_variable(*var, true).is_empty())
+                .collect::<Vec<_>>();
+
+            let mut same_readouts = remaining.clone();
+            for (var, value) in self.readouts.iter().zip(&valuation) {
+                same_readouts = same_readouts.fix_network_variable(*var, *value);
+            }
+            remaining = remaining.minus(&same_readouts);
+            result.push(valuation);
+        }
+        result.sort();
+        result
+    }
+}
+
+/// Writes the readout valuations, one character per readout variable, separated by spaces.
+impl Display for Projection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let valuations = self
+            .valuations()
+            .into_iter()
+            .map(|valuation| {
+                valuation
+                    .into_iter()
+                    .map(|value| if value { '1' } else { '0' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", valuations.join(" "))
+    }
+}
+
+/// Projects every SCC onto the `readouts` (existentially quantifying all the other network
+/// variables) and merges the SCCs with identical projections.
+///
+/// The projections are ordered by the first SCC with that projection.
+pub fn project<G: SymbolicTransitionSystem>(
+    graph: &G,
+    sccs: &[GraphColoredVertices],
+    readouts: &[VariableId],
+) -> Vec<Projection> {
+    let context = graph.symbolic_context();
+    let hidden = graph
+        .variables()
+        .into_iter()
+        .filter(|var| !readouts.contains(var))
+        .map(|var| context.get_state_variable(var))
+        .collect::<Vec<_>>();
+
+    let mut result = Vec::<Projection>::new();
+    let mut index = HashMap::<GraphColoredVertices, usize>::new();
+    for (i, scc) in sccs.iter().enumerate() {
+        let values = scc.copy(scc.as_bdd().exists(&hidden));
+        match index.get(&values) {
+            Some(existing) => result[*existing].sccs.push(i),
+            None => {
+                index.insert(values.clone(), result.len());
+                result.push(Projection {
+                    readouts: readouts.to_vec(),
+                    values,
+                    sccs: vec![i],
+                });
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::cycle_network;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+    #[test]
+    fn projection_basic() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+        let state = |a, b| graph.mk_subspace(&[(var_a, a), (var_b, b), (var_c, true)]);
+
+        let sccs = vec![
+            graph.mk_subspace(&[(var_c, false)]),
+            state(true, true),
+            state(true, false),
+            state(false, true),
+            state(false, false),
+        ];
+
+        let projections = project(&graph, &sccs, &[var_c]);
+        assert_eq!(projections.len(), 2);
+        assert_eq!(projections[0].sccs, vec![0]);
+        assert_eq!(projections[0].valuations(), vec![vec![false]]);
+        assert_eq!(projections[1].sccs, vec![1, 2, 3, 4]);
+        assert_eq!(projections[1].values, graph.mk_subspace(&[(var_c, true)]));
+
+        let projections = project(&graph, &sccs, &[var_b, var_a]);
+        assert_eq!(projections.len(), 5);
+        assert_eq!(projections[0].to_string(), "00 01 10 11");
+        assert_eq!(projections[2].to_string(), "01");
+
+        let projections = project(&graph, &sccs, &[var_a]);
+        let merged = projections
+            .iter()
+            .map(|it| it.sccs.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(merged, vec![vec![0], vec![1, 2], vec![3, 4]]);
+        assert_eq!(projections[0].to_string(), "0 1");
+        assert_eq!(projections[2].to_string(), "0");
+
+        // no readouts: everything is merged
+        let projections = project(&graph, &sccs, &[]);
+        assert_eq!(projections.len(), 1);
+        assert_eq!(projections[0].values, *graph.unit_colored_vertices());
+    }
+}
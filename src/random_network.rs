@@ -0,0 +1,186 @@
+//! Generator of random (non-parametrized) Boolean networks for property-based testing.
+//!
+//! Every variable gets between one and `max_in_degree` regulators. The update function of
+//! a variable is a random *read-once* formula (every regulator appears exactly once,
+//! combined by random conjunctions and disjunctions), with the regulator negated for
+//! inhibitions. Such a function is monotonic in, and depends on, every regulator, so the
+//! regulatory graph is always consistent with the update functions.
+//!
+//! The amount of cycles is controlled by `cycle_density`: the variables are (implicitly)
+//! ordered, and a regulation either goes "forward" in this order (cannot close a cycle),
+//! or "backward" (including self-regulations; can close a cycle).
+
+use biodivine_lib_param_bn::BinaryOp;
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::FnUpdate;
+use biodivine_lib_param_bn::Monotonicity;
+use biodivine_lib_param_bn::RegulatoryGraph;
+use biodivine_lib_param_bn::VariableId;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RandomNetworkConfig {
+    pub num_vars: usize,
+    /// Every variable has at least one, and at most this many regulators.
+    pub max_in_degree: usize,
+    /// The probability that a regulation is an activation (otherwise it is an inhibition).
+    pub activation_probability: f64,
+    /// The probability that a regulation goes "backward" (and thus can close a cycle).
+    pub cycle_density: f64,
+}
+
+impl Default for RandomNetworkConfig {
+    fn default() -> Self {
+        RandomNetworkConfig {
+            num_vars: 8,
+            max_in_degree: 3,
+            activation_probability: 0.5,
+            cycle_density: 0.5,
+        }
+    }
+}
+
+/// Generates a random Boolean network; the same `seed` always yields the same network.
+pub fn random_network(config: RandomNetworkConfig, seed: u64) -> BooleanNetwork {
+    assert!(config.num_vars > 0);
+    assert!(config.max_in_degree > 0);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let names = (0..config.num_vars)
+        .map(|i| format!("v_{i}"))
+        .collect::<Vec<_>>();
+    let mut graph = RegulatoryGraph::new(names.clone());
+
+    let mut regulations = Vec::new();
+    for target in 0..config.num_vars {
+        let in_degree = rng.gen_range(1..=config.max_in_degree.min(config.num_vars));
+
+        // forward regulators precede the target, backward ones do not
+        let (mut forward, mut backward) = (
+            (0..target).collect::<Vec<_>>(),
+            (target..config.num_vars).collect::<Vec<_>>(),
+        );
+        forward.shuffle(&mut rng);
+        backward.shuffle(&mut rng);
+
+        let mut regulators = Vec::new();
+        for _ in 0..in_degree {
+            let go_backward = rng.gen_bool(config.cycle_density);
+            let regulator = match go_backward {
+                true => backward.pop().or_else(|| forward.pop()),
+                false => forward.pop().or_else(|| backward.pop()),
+            };
+            regulators.push(regulator.expect("in-degree is at most the number of variables"));
+        }
+
+        for regulator in regulators {
+            let monotonicity = match rng.gen_bool(config.activation_probability) {
+                true => Monotonicity::Activation,
+                false => Monotonicity::Inhibition,
+            };
+            graph
+                .add_regulation(&names[regulator], &names[target], true, Some(monotonicity))
+                .expect("regulators are unique");
+            regulations.push((regulator, target, monotonicity));
+        }
+    }
+
+    let mut network = BooleanNetwork::new(graph);
+    for target in 0..config.num_vars {
+        let mut literals = regulations
+            .iter()
+            .filter(|(_, it, _)| *it == target)
+            .map(|(regulator, _, monotonicity)| {
+                let literal = FnUpdate::mk_var(VariableId::from_index(*regulator));
+                match monotonicity {
+                    Monotonicity::Activation => literal,
+                    Monotonicity::Inhibition => FnUpdate::mk_not(literal),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // combine two random sub-formulas until a single formula remains
+        while literals.len() > 1 {
+            let left = literals.swap_remove(rng.gen_range(0..literals.len()));
+            let right = literals.swap_remove(rng.gen_range(0..literals.len()));
+            let operator = match rng.gen_bool(0.5) {
+                true => BinaryOp::And,
+                false => BinaryOp::Or,
+            };
+            literals.push(FnUpdate::mk_binary(operator, left, right));
+        }
+
+        let function = literals.pop().expect("every variable has a regulator");
+        network
+            .add_update_function(VariableId::from_index(target), function)
+            .expect("the function is consistent with the regulations");
+    }
+
+    network
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use proptest::prelude::*;
+
+    #[test]
+    fn random_network_is_deterministic() {
+        let config = RandomNetworkConfig::default();
+
+        assert_eq!(random_network(config, 42), random_network(config, 42));
+        assert_ne!(random_network(config, 42), random_network(config, 43));
+    }
+
+    #[test]
+    fn random_network_without_cycles() {
+        let config = RandomNetworkConfig {
+            cycle_density: 0.0,
+            ..Default::default()
+        };
+        let network = random_network(config, 0);
+
+        // only the first variable has no "forward" regulator to pick
+        for regulation in network.as_graph().regulations() {
+            assert!(
+                regulation.regulator < regulation.target
+                    || regulation.target == VariableId::from_index(0)
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn random_network_is_consistent(
+            num_vars in 1usize..12,
+            max_in_degree in 1usize..5,
+            activation_probability in 0.0f64..=1.0,
+            cycle_density in 0.0f64..=1.0,
+            seed in any::<u64>(),
+        ) {
+            let config = RandomNetworkConfig {
+                num_vars,
+                max_in_degree,
+                activation_probability,
+                cycle_density,
+            };
+            let network = random_network(config, seed);
+
+            prop_assert_eq!(network.num_vars(), num_vars);
+            prop_assert_eq!(network.num_parameters(), 0);
+            prop_assert_eq!(network.num_implicit_parameters(), 0);
+            for var in network.variables() {
+                let in_degree = network.regulators(var).len();
+                prop_assert!(in_degree >= 1 && in_degree <= max_in_degree);
+            }
+
+            // the update functions must agree with the declared monotonicity and observability
+            prop_assert!(SymbolicAsyncGraph::new(&network).is_ok());
+        }
+    }
+}
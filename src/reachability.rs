@@ -0,0 +1,105 @@
+//! Reachability queries between SCCs (or any other sets of states) of the decomposition.
+
+use crate::chain::bwd_saturation_within;
+use crate::chain::fwd_saturation;
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+
+/// Whether some state of `from` can reach some state of `to`.
+pub fn can_reach<G: SymbolicTransitionSystem>(
+    graph: &G,
+    from: &GraphColoredVertices,
+    to: &GraphColoredVertices,
+) -> bool {
+    !fwd_saturation(graph, from).intersect(to).is_empty()
+}
+
+/// All states on some path from `from` to `to`, excluding the states of `from` and `to`
+/// themselves.
+///
+/// The result is empty if `to` is not reachable from `from`, but also if every path
+/// between them is a single transition.
+pub fn intermediate_states<G: SymbolicTransitionSystem>(
+    graph: &G,
+    from: &GraphColoredVertices,
+    to: &GraphColoredVertices,
+) -> GraphColoredVertices {
+    let reachable = fwd_saturation(graph, from);
+    if reachable.intersect(to).is_empty() {
+        return graph.empty_colored_vertices().clone();
+    }
+
+    // only the states reachable from `from` can be on such a path
+    let reaching = bwd_saturation_within(graph, &to.intersect(&reachable), &reachable);
+    reaching.minus(from).minus(to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explicit::ExplicitAsyncGraph;
+    use crate::test_utils::arb_network;
+    use crate::test_utils::cycle_network;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
+
+    #[test]
+    fn reachability_cycle_to_fixed_point() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+
+        let cycle = graph.mk_subspace(&[(var_c, false)]);
+        let fixed_point = graph.mk_subspace(&[(var_a, false), (var_b, false), (var_c, true)]);
+
+        assert!(can_reach(&graph, &cycle, &fixed_point));
+        assert!(!can_reach(&graph, &fixed_point, &cycle));
+
+        let expected = graph.mk_subspace(&[(var_c, true)]).minus(&fixed_point);
+        assert_eq!(intermediate_states(&graph, &cycle, &fixed_point), expected);
+        assert!(intermediate_states(&graph, &fixed_point, &cycle).is_empty());
+
+        // a state that is not on the way to the fixed point
+        let target = graph.mk_subspace(&[(var_a, true), (var_b, false), (var_c, true)]);
+        let expected = graph.mk_subspace(&[(var_a, true), (var_b, true), (var_c, true)]);
+        assert_eq!(intermediate_states(&graph, &cycle, &target), expected);
+    }
+
+    proptest! {
+        #[test]
+        fn compare_reachability_with_explicit_random(bn in arb_network(6)) {
+            let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+            let explicit = ExplicitAsyncGraph::new(&bn).unwrap();
+
+            let sccs = explicit.scc_decomposition();
+            for from in sccs.iter().take(8) {
+                let reachable = explicit.reachable(from[0]);
+                for to in sccs.iter().take(8) {
+                    let symbolic_from = explicit.to_symbolic(&graph, from);
+                    let symbolic_to = explicit.to_symbolic(&graph, to);
+
+                    prop_assert_eq!(
+                        can_reach(&graph, &symbolic_from, &symbolic_to),
+                        reachable.contains(&to[0])
+                    );
+
+                    // the states reachable from `from` that reach `to`
+                    let intermediate = reachable
+                        .iter()
+                        .copied()
+                        .filter(|it| !from.contains(it) && !to.contains(it))
+                        .filter(|it| explicit.reachable(*it).contains(&to[0]))
+                        .collect::<Vec<_>>();
+                    prop_assert_eq!(
+                        intermediate_states(&graph, &symbolic_from, &symbolic_to),
+                        explicit.to_symbolic(&graph, &intermediate)
+                    );
+                }
+            }
+        }
+    }
+}
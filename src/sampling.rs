@@ -0,0 +1,228 @@
+//! Sampling of representative states from (possibly huge) symbolic sets, e.g. SCCs.
+
+use crate::hamming::furthest_within;
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_bdd::BddPointer;
+use biodivine_lib_bdd::BddVariable;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::cmp::Reverse;
+
+/// Draws `k` states of `set` independently and uniformly at random (so the same state can be
+/// drawn repeatedly); the same `seed` always yields the same states.
+///
+/// For a colored set, the (state, color) pairs are drawn uniformly. Every drawn state is
+/// returned as a singleton.
+pub fn sample_uniform<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: &GraphColoredVertices,
+    k: usize,
+    seed: u64,
+) -> Vec<GraphColoredVertices> {
+    assert!(!set.is_empty());
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let context = graph.symbolic_context();
+    let bdd = set.as_bdd();
+    let log_density = log_densities(bdd);
+
+    // only the variables identifying a (state, color) pair are fixed in the result
+    let mut identifying = context.state_variables().clone();
+    identifying.extend(context.parameter_variables());
+
+    (0..k)
+        .map(|_| {
+            let mut valuation = Vec::with_capacity(identifying.len());
+            let mut node = bdd.root_pointer();
+            for var in 0..context.bdd_variable_set().num_vars() {
+                let var = BddVariable::from_index(usize::from(var));
+                let value = if !node.is_terminal() && bdd.var_of(node) == var {
+                    // go high with the probability proportional to the number of valuations
+                    let (low, high) = (bdd.low_link_of(node), bdd.high_link_of(node));
+                    let log_high = log_density[high.to_index()];
+                    let log_total = log_add_exp(log_density[low.to_index()], log_high);
+                    let value = rng.gen_bool((log_high - log_total).exp().clamp(0.0, 1.0));
+                    node = if value { high } else { low };
+                    value
+                } else {
+                    // the variable is not constrained on this path
+                    rng.gen_bool(0.5)
+                };
+                if identifying.contains(&var) {
+                    valuation.push((var, value));
+                }
+            }
+
+            set.copy(bdd.select(&valuation))
+        })
+        .collect()
+}
+
+/// Picks up to `k` different states of `set` that are as far (in terms of the Hamming
+/// distance) from each other as possible.
+///
+/// The states are picked greedily: every next state is the furthest one from all the states
+/// picked so far. Fewer than `k` states are returned only if `set` is smaller than `k`.
+pub fn sample_diverse<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: &GraphColoredVertices,
+    k: usize,
+) -> Vec<GraphColoredVertices> {
+    let mut picked = Vec::<GraphColoredVertices>::new();
+    let mut picked_union = graph.empty_colored_vertices().clone();
+    let mut remaining = set.clone();
+
+    while picked.len() < k && !remaining.is_empty() {
+        let next = match picked.is_empty() {
+            true => remaining.pick_singleton(),
+            false => furthest_within(graph, &picked_union, &remaining),
+        };
+
+        picked_union = picked_union.union(&next);
+        remaining = remaining.minus(&next);
+        picked.push(next);
+    }
+
+    picked
+}
+
+/// For every node of the `bdd`, the natural logarithm of the fraction of the valuations
+/// (of the variables from the node's one downwards) that satisfy the node.
+///
+/// Densities (rather than counts) never overflow and logarithms do not underflow, even for
+/// sets with astronomically many states.
+fn log_densities(bdd: &Bdd) -> Vec<f64> {
+    let num_nodes = bdd.size();
+    let mut log_density = vec![f64::NEG_INFINITY; num_nodes];
+    if num_nodes > 1 {
+        log_density[1] = 0.0; // the `true` terminal
+    }
+
+    // every node is processed after both of its children (which have larger variables)
+    let mut decision_nodes = (2..num_nodes)
+        .map(BddPointer::from_index)
+        .collect::<Vec<_>>();
+    decision_nodes.sort_unstable_by_key(|it| Reverse(bdd.var_of(*it)));
+
+    for node in decision_nodes {
+        let log_low = log_density[bdd.low_link_of(node).to_index()];
+        let log_high = log_density[bdd.high_link_of(node).to_index()];
+        log_density[node.to_index()] = log_add_exp(log_low, log_high) - std::f64::consts::LN_2;
+    }
+
+    log_density
+}
+
+/// `ln(e^a + e^b)`, computed without overflows.
+fn log_add_exp(a: f64, b: f64) -> f64 {
+    let max = a.max(b);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + ((a - max).exp() + (b - max).exp()).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hamming::hamming_distance;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use std::collections::HashMap;
+
+    fn four_variable_graph() -> SymbolicAsyncGraph {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> A
+            B -> B
+            C -> C
+            D -> D
+            $A: A
+            $B: B
+            $C: C
+            $D: D
+            "#,
+        )
+        .unwrap();
+        SymbolicAsyncGraph::new(&bn).unwrap()
+    }
+
+    #[test]
+    fn sample_uniform_frequencies() {
+        let graph = four_variable_graph();
+        let vars = graph.variables().collect::<Vec<_>>();
+
+        // a set with an unbalanced BDD: one state with `A`, and 8 states without `A`
+        let with_a = graph.mk_subspace(&[
+            (vars[0], true),
+            (vars[1], true),
+            (vars[2], true),
+            (vars[3], true),
+        ]);
+        let without_a = graph.mk_subspace(&[(vars[0], false)]);
+        let set = with_a.union(&without_a);
+
+        let samples = sample_uniform(&graph, &set, 9_000, 42);
+        assert_eq!(samples, sample_uniform(&graph, &set, 9_000, 42));
+
+        let mut frequencies = HashMap::<GraphColoredVertices, usize>::new();
+        for sample in samples {
+            assert!(sample.is_singleton());
+            assert!(sample.is_subset(&set));
+            *frequencies.entry(sample).or_default() += 1;
+        }
+
+        // every state should be drawn roughly 1000 times
+        assert_eq!(frequencies.len(), 9);
+        for count in frequencies.values() {
+            assert!((800..1200).contains(count), "{count}");
+        }
+    }
+
+    #[test]
+    fn sample_uniform_huge_set() {
+        // 2^200 states, far beyond the range of the primitive integers
+        let names = (0..200).map(|i| format!("v_{i}")).collect::<Vec<_>>();
+        let bn = BooleanNetwork::try_from(
+            names
+                .iter()
+                .map(|name| format!("{name} -> {name}\n${name}: {name}\n"))
+                .collect::<String>()
+                .as_str(),
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+
+        let samples = sample_uniform(&graph, graph.unit_colored_vertices(), 10, 0);
+        assert_eq!(samples.len(), 10);
+        assert!(samples.iter().all(|it| it.is_singleton()));
+        // collisions are (almost) impossible
+        assert_ne!(samples[0], samples[1]);
+    }
+
+    #[test]
+    fn sample_diverse_states() {
+        let graph = four_variable_graph();
+        let unit = graph.unit_colored_vertices();
+
+        let samples = sample_diverse(&graph, unit, 2);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(hamming_distance(&graph, &samples[0], &samples[1]), Some(4));
+
+        // the third state is half-way between the first two
+        let samples = sample_diverse(&graph, unit, 3);
+        assert_eq!(hamming_distance(&graph, &samples[0], &samples[2]), Some(2));
+        assert_eq!(hamming_distance(&graph, &samples[1], &samples[2]), Some(2));
+
+        // not enough states
+        let vars = graph.variables().collect::<Vec<_>>();
+        let small = graph.mk_subspace(&[(vars[0], true), (vars[1], true), (vars[2], true)]);
+        let samples = sample_diverse(&graph, &small, 5);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].union(&samples[1]), small);
+    }
+}
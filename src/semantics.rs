@@ -0,0 +1,573 @@
+//! Update semantics other than the (fully) asynchronous one.
+//!
+//! The asynchronous semantics is implemented by `SymbolicAsyncGraph` directly. Here, we
+//! provide a [SymbolicRelationGraph] - a transition system given by an explicit symbolic
+//! transition relation - which is built from the same `BooleanNetwork` for the
+//! synchronous and the generalized asynchronous semantics.
+//!
+//! The relation is encoded using one *extra state variable* per network variable (the
+//! "primed" copy of the variable), which is placed right after the network variable in
+//! the BDD variable ordering. The sets produced by the graph never depend on the extra
+//! variables, hence they behave just like any other `GraphColoredVertices`.
+//!
+//! Apart from Boolean networks, the relation can also be built by hand (see
+//! [SymbolicRelationGraph::mk_context] and [SymbolicRelationGraph::new]), e.g. to decompose
+//! the state space of a (safe) Petri net.
+
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_bdd::BddVariable;
+use biodivine_lib_bdd::op_function::and;
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::FnUpdate;
+use biodivine_lib_param_bn::RegulatoryGraph;
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicContext;
+
+use crate::transition_system::SymbolicTransitionSystem;
+
+/// A transition system given by a symbolic transition relation over the states of a
+/// Boolean network.
+#[derive(Clone)]
+pub struct SymbolicRelationGraph {
+    context: SymbolicContext,
+    unit: GraphColoredVertices,
+    empty: GraphColoredVertices,
+    state_variables: Vec<BddVariable>,
+    primed_variables: Vec<BddVariable>,
+    /// `x <=> x'` for every network variable; used to "rename" sets.
+    identity: Bdd,
+    /// All the transitions (never self-loops).
+    relation: Bdd,
+    /// The transitions that change the value of the given network variable.
+    var_relations: Vec<Bdd>,
+    /// The network the relation was built from (`None` for hand-built relations).
+    network: Option<BooleanNetwork>,
+}
+
+impl SymbolicRelationGraph {
+    /// The synchronous semantics of the `network`: all the variables are updated
+    /// at once in every step.
+    ///
+    /// The states that are fixed points of the update functions have no transition
+    /// (self-loops are not represented).
+    pub fn synchronous(network: &BooleanNetwork) -> Result<Self, String> {
+        Self::from_update_semantics(network, |updated, _| updated)
+    }
+
+    /// The generalized asynchronous semantics of the `network`: any nonempty subset
+    /// of the variables is updated at once in a single step.
+    ///
+    /// Subsumes both the asynchronous and the synchronous semantics.
+    pub fn generalized_asynchronous(network: &BooleanNetwork) -> Result<Self, String> {
+        Self::from_update_semantics(network, |updated, kept| updated.or(&kept))
+    }
+
+    /// A transition system given by a hand-built `relation` on the states `unit`.
+    ///
+    /// The `context` must have exactly one extra state variable per network variable
+    /// (the "primed" copy of the variable, i.e. the value after the transition); see
+    /// [Self::mk_context]. The `unit` set may only depend on the state (and parameter)
+    /// variables. The `relation` may contain self-loops and transitions leading outside
+    /// of `unit`; these are removed.
+    pub fn new(context: SymbolicContext, unit: Bdd, relation: Bdd) -> Result<Self, String> {
+        for var in context.network_variables() {
+            let extra = context.extra_state_variables(var).len();
+            if extra != 1 {
+                return Err(format!(
+                    "Variable {} has {} extra state variables; expected 1.",
+                    context.get_network_variable_name(var),
+                    extra
+                ));
+            }
+        }
+
+        let extra_support = unit
+            .support_set()
+            .intersection(
+                &context
+                    .all_extra_state_variables()
+                    .iter()
+                    .copied()
+                    .collect(),
+            )
+            .count();
+        if extra_support > 0 {
+            return Err("The unit set depends on extra state variables.".to_string());
+        }
+
+        Ok(Self::from_context_and_relation(context, unit, relation))
+    }
+
+    /// A symbolic context for a hand-built relation over the given state `variables`.
+    ///
+    /// Each variable gets one extra state variable (its "primed" copy). There are no
+    /// parameters in the context.
+    pub fn mk_context(variables: &[&str]) -> Result<SymbolicContext, String> {
+        let names = variables
+            .iter()
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>();
+        let mut graph = RegulatoryGraph::new(names);
+        for var in variables {
+            graph.add_regulation(var, var, false, None)?;
+        }
+
+        // the update functions are irrelevant (only the relation matters), but the
+        // variables must not have implicit ones; those would introduce parameters
+        let mut network = BooleanNetwork::new(graph);
+        for var in network.variables() {
+            network.add_update_function(var, FnUpdate::mk_var(var))?;
+        }
+
+        let extra = network
+            .variables()
+            .map(|var| (var, 1))
+            .collect::<HashMap<VariableId, u16>>();
+        SymbolicContext::with_extra_state_variables(&network, &extra)
+    }
+
+    /// Builds the relation as a conjunction of per-variable constraints.
+    ///
+    /// For every network variable, `var_step` combines the constraint that the variable
+    /// is *updated* (`x' <=> f(x)`) with the constraint that it *keeps* its value
+    /// (`x' <=> x`) into the constraint of the semantics.
+    fn from_update_semantics(
+        network: &BooleanNetwork,
+        var_step: impl Fn(Bdd, Bdd) -> Bdd,
+    ) -> Result<Self, String> {
+        let extra = network
+            .variables()
+            .map(|var| (var, 1))
+            .collect::<HashMap<VariableId, u16>>();
+        let context = SymbolicContext::with_extra_state_variables(network, &extra)?;
+
+        // the async graph takes care of the update functions and of the admissible colors
+        let async_graph = SymbolicAsyncGraph::with_custom_context(
+            network,
+            context.clone(),
+            context.mk_constant(true),
+        )?;
+
+        let mut relation = context.mk_constant(true);
+        for var in network.variables() {
+            let primed = context.mk_extra_state_variable_is_true(var, 0);
+            let updated = primed.iff(async_graph.get_symbolic_fn_update(var));
+            let kept = primed.iff(&context.mk_state_variable_is_true(var));
+
+            relation = relation.and(&var_step(updated, kept));
+        }
+
+        let mut graph = Self::from_context_and_relation(
+            context,
+            async_graph.unit_colored_vertices().as_bdd().clone(),
+            relation,
+        );
+        graph.network = Some(network.clone());
+        Ok(graph)
+    }
+
+    /// Assembles the graph from a `context` with one extra state variable per network
+    /// variable, the `unit` set and a (not yet normalized) transition `relation`.
+    ///
+    /// The relation is normalized by removing the self-loops and the transitions that
+    /// leave the `unit` set.
+    fn from_context_and_relation(context: SymbolicContext, unit: Bdd, relation: Bdd) -> Self {
+        let state_variables = context.state_variables().clone();
+        let primed_variables = context
+            .network_variables()
+            .map(|var| context.get_extra_state_variable(var, 0))
+            .collect::<Vec<_>>();
+
+        let identity = context
+            .network_variables()
+            .map(|var| {
+                context
+                    .mk_extra_state_variable_is_true(var, 0)
+                    .iff(&context.mk_state_variable_is_true(var))
+            })
+            .fold(context.mk_constant(true), |acc, it| acc.and(&it));
+
+        let unit = GraphColoredVertices::new(unit, &context);
+        let empty = GraphColoredVertices::new(context.mk_constant(false), &context);
+        let no_relation = context.mk_constant(false);
+
+        let mut graph = SymbolicRelationGraph {
+            context,
+            unit,
+            empty,
+            state_variables,
+            primed_variables,
+            identity,
+            relation: no_relation,
+            var_relations: Vec::new(),
+            network: None,
+        };
+
+        let primed_unit = graph.prime(graph.unit.as_bdd());
+        let relation = relation
+            .and_not(&graph.identity)
+            .and(graph.unit.as_bdd())
+            .and(&primed_unit);
+        graph.set_relation(relation);
+
+        graph
+    }
+
+    /// Sets the relation (and the per-variable relations derived from it).
+    fn set_relation(&mut self, relation: Bdd) {
+        self.var_relations = self
+            .context
+            .network_variables()
+            .map(|var| {
+                let changed = self
+                    .context
+                    .mk_extra_state_variable_is_true(var, 0)
+                    .xor(&self.context.mk_state_variable_is_true(var));
+                relation.and(&changed)
+            })
+            .collect();
+        self.relation = relation;
+    }
+
+    /// Renames the state variables of the `bdd` to their primed copies.
+    fn prime(&self, bdd: &Bdd) -> Bdd {
+        Bdd::binary_op_with_exists(bdd, &self.identity, and, &self.state_variables)
+    }
+
+    /// Renames the primed variables of the `bdd` back to the state variables.
+    fn unprime(&self, bdd: &Bdd) -> Bdd {
+        Bdd::binary_op_with_exists(bdd, &self.identity, and, &self.primed_variables)
+    }
+
+    /// Successors of `initial` w.r.t. the given `relation`.
+    fn relation_post(
+        &self,
+        relation: &Bdd,
+        initial: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        let primed_post =
+            Bdd::binary_op_with_exists(initial.as_bdd(), relation, and, &self.state_variables);
+        initial.copy(self.unprime(&primed_post))
+    }
+
+    /// Predecessors of `initial` w.r.t. the given `relation`.
+    fn relation_pre(&self, relation: &Bdd, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        let primed_initial = self.prime(initial.as_bdd());
+        initial.copy(Bdd::binary_op_with_exists(
+            &primed_initial,
+            relation,
+            and,
+            &self.primed_variables,
+        ))
+    }
+}
+
+impl SymbolicTransitionSystem for SymbolicRelationGraph {
+    fn symbolic_context(&self) -> &SymbolicContext {
+        &self.context
+    }
+
+    fn unit_colored_vertices(&self) -> &GraphColoredVertices {
+        &self.unit
+    }
+
+    fn empty_colored_vertices(&self) -> &GraphColoredVertices {
+        &self.empty
+    }
+
+    fn variables(&self) -> Vec<VariableId> {
+        self.context.network_variables().collect()
+    }
+
+    fn restrict(&self, set: &GraphColoredVertices) -> Self {
+        let unit = self.unit.intersect(set);
+        let relation = self
+            .relation
+            .and(unit.as_bdd())
+            .and(&self.prime(unit.as_bdd()));
+
+        let mut graph = self.clone();
+        graph.unit = unit;
+        graph.set_relation(relation);
+        graph
+    }
+
+    fn post(&self, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        self.relation_post(&self.relation, initial)
+    }
+
+    fn pre(&self, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        self.relation_pre(&self.relation, initial)
+    }
+
+    fn var_post_out(
+        &self,
+        var: VariableId,
+        initial: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        self.relation_post(&self.var_relations[var.to_index()], initial)
+            .minus(initial)
+    }
+
+    fn var_pre_out(&self, var: VariableId, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        self.relation_pre(&self.var_relations[var.to_index()], initial)
+            .minus(initial)
+    }
+
+    fn can_post_within(&self, set: &GraphColoredVertices) -> GraphColoredVertices {
+        let primed_set = self.prime(set.as_bdd());
+        let within = self.relation.and(set.as_bdd());
+        set.copy(Bdd::binary_op_with_exists(
+            &within,
+            &primed_set,
+            and,
+            &self.primed_variables,
+        ))
+    }
+
+    fn can_pre_within(&self, set: &GraphColoredVertices) -> GraphColoredVertices {
+        let primed_set = self.prime(set.as_bdd());
+        let within = self.relation.and(&primed_set);
+        let primed_result =
+            Bdd::binary_op_with_exists(set.as_bdd(), &within, and, &self.state_variables);
+        set.copy(self.unprime(&primed_result))
+    }
+
+    fn as_network(&self) -> Option<&BooleanNetwork> {
+        self.network.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Config;
+    use crate::chain::Strategy;
+    use crate::chain::TrimLvl;
+    use crate::chain::chain;
+    use crate::fwd_bwd::fwd_bwd_scc_decomposition_naive;
+    use crate::test_utils::skip_large_model;
+    use std::collections::HashSet;
+    use test_generator::test_resources;
+
+    /// `A` copies `B` and `B` copies `A`.
+    ///
+    /// Asynchronously, both `01` and `10` fall into a fixed point (`00` or `11`),
+    /// synchronously, the two states swap forever.
+    fn swap_network() -> BooleanNetwork {
+        BooleanNetwork::try_from(
+            r#"
+            B -> A
+            A -> B
+            $A: B
+            $B: A
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn swap_cycle<G: SymbolicTransitionSystem>(graph: &G) -> GraphColoredVertices {
+        let vars = graph.variables();
+        let unit = graph.unit_colored_vertices();
+        let a_xor_b = unit
+            .fix_network_variable(vars[0], true)
+            .fix_network_variable(vars[1], false)
+            .union(
+                &unit
+                    .fix_network_variable(vars[0], false)
+                    .fix_network_variable(vars[1], true),
+            );
+        assert_eq!(a_xor_b.exact_cardinality(), 2u32.into());
+        a_xor_b
+    }
+
+    #[test]
+    fn synchronous_swap() {
+        let graph = SymbolicRelationGraph::synchronous(&swap_network()).unwrap();
+        let cycle = swap_cycle(&graph);
+
+        // fixed points have no (self-loop) transitions
+        assert_eq!(graph.post(graph.unit_colored_vertices()), cycle);
+        assert_eq!(graph.pre(&cycle), cycle);
+        assert_eq!(graph.can_post_within(graph.unit_colored_vertices()), cycle);
+
+        let sccs = chain(graph, Config::default()).collect::<Vec<_>>();
+        assert_eq!(sccs, vec![cycle]);
+    }
+
+    #[test]
+    fn generalized_asynchronous_swap() {
+        let graph = SymbolicRelationGraph::generalized_asynchronous(&swap_network()).unwrap();
+        let cycle = swap_cycle(&graph);
+
+        // the cycle can still be escaped by updating only one of the variables
+        assert_eq!(graph.post(&cycle), graph.unit_colored_vertices().clone());
+        assert!(
+            graph
+                .post(&graph.unit_colored_vertices().minus(&cycle))
+                .is_empty()
+        );
+
+        let sccs = chain(graph, Config::default()).collect::<Vec<_>>();
+        assert_eq!(sccs, vec![cycle]);
+    }
+
+    #[test]
+    fn asynchronous_swap() {
+        let graph = SymbolicAsyncGraph::new(&swap_network()).unwrap();
+
+        assert_eq!(chain(graph, Config::default()).count(), 0);
+    }
+
+    #[test]
+    fn restricted_relation_graph() {
+        let graph = SymbolicRelationGraph::generalized_asynchronous(&swap_network()).unwrap();
+        let cycle = swap_cycle(&graph);
+
+        let restricted = graph.restrict(&cycle);
+        assert_eq!(restricted.unit_colored_vertices(), &cycle);
+        assert_eq!(restricted.post(graph.unit_colored_vertices()), cycle);
+        assert_eq!(restricted.pre(graph.unit_colored_vertices()), cycle);
+        assert_eq!(restricted.can_pre_within(&cycle), cycle);
+
+        for var in restricted.variables() {
+            // every transition within the cycle flips both variables
+            assert_eq!(
+                restricted.var_post_out(var, &cycle),
+                *graph.empty_colored_vertices()
+            );
+        }
+    }
+
+    /// A token cycling through three places (`P -> Q -> R -> P`) of a safe Petri net,
+    /// and a transition putting the token into `P` when there is none.
+    fn token_ring() -> SymbolicRelationGraph {
+        let context = SymbolicRelationGraph::mk_context(&["P", "Q", "R"]).unwrap();
+        let vars = context.network_variables().collect::<Vec<_>>();
+
+        let marking = |primed: bool, marked: usize| {
+            vars.iter()
+                .enumerate()
+                .map(|(i, var)| {
+                    let bdd = match primed {
+                        true => context.mk_extra_state_variable_is_true(*var, 0),
+                        false => context.mk_state_variable_is_true(*var),
+                    };
+                    match i == marked {
+                        true => bdd,
+                        false => bdd.not(),
+                    }
+                })
+                .fold(context.mk_constant(true), |acc, it| acc.and(&it))
+        };
+        let transition = |from: usize, to: usize| marking(false, from).and(&marking(true, to));
+
+        let relation = transition(0, 1)
+            .or(&transition(1, 2))
+            .or(&transition(2, 0))
+            // `usize::MAX` marks no place -> the empty marking
+            .or(&transition(usize::MAX, 0));
+
+        // one-hot markings and the empty marking
+        let unit = (0..3)
+            .map(|place| marking(false, place))
+            .fold(marking(false, usize::MAX), |acc, it| acc.or(&it));
+
+        SymbolicRelationGraph::new(context, unit, relation).unwrap()
+    }
+
+    #[test]
+    fn hand_built_relation() {
+        let graph = token_ring();
+        let unit = graph.unit_colored_vertices();
+        assert_eq!(unit.exact_cardinality(), 4u32.into());
+
+        // the empty marking is the only one without a predecessor
+        let empty_marking = unit.minus(&graph.can_pre_within(unit));
+        assert!(empty_marking.is_singleton());
+        let ring = unit.minus(&empty_marking);
+
+        assert_eq!(graph.post(unit), ring);
+        assert_eq!(graph.pre(&ring), *unit);
+        assert!(graph.post(&empty_marking).is_subset(&ring));
+
+        for strategy in [
+            Strategy::Chain,
+            Strategy::Saturation,
+            Strategy::SaturationHamming,
+            Strategy::SaturationWeightedHamming(vec![3, 1, 2].into()),
+        ] {
+            for trim_lvl in [TrimLvl::None, TrimLvl::StartOnly, TrimLvl::Full] {
+                let sccs = chain(
+                    graph.clone(),
+                    Config {
+                        trim_lvl,
+                        strategy: strategy.clone(),
+                        ..Default::default()
+                    },
+                )
+                .collect::<Vec<_>>();
+                assert_eq!(sccs, vec![ring.clone()]);
+            }
+        }
+
+        let fwd_bwd_sccs = fwd_bwd_scc_decomposition_naive(graph).collect::<Vec<_>>();
+        assert_eq!(fwd_bwd_sccs, vec![ring]);
+    }
+
+    #[test]
+    fn hand_built_relation_invalid_context() {
+        let bn = swap_network();
+        let context = SymbolicContext::new(&bn).unwrap();
+        let unit = context.mk_constant(true);
+
+        assert!(SymbolicRelationGraph::new(context, unit.clone(), unit).is_err());
+    }
+
+    fn compare_semantics_with_fwd_bwd(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        let graphs = [
+            SymbolicRelationGraph::synchronous(&bn).unwrap(),
+            SymbolicRelationGraph::generalized_asynchronous(&bn).unwrap(),
+        ];
+
+        for graph in graphs {
+            let fwd_bwd_scc_set =
+                fwd_bwd_scc_decomposition_naive(graph.clone()).collect::<HashSet<_>>();
+
+            for strategy in [
+                Strategy::Chain,
+                Strategy::Saturation,
+                Strategy::SaturationHamming,
+            ] {
+                let chain_scc_set = chain(
+                    graph.clone(),
+                    Config {
+                        trim_lvl: TrimLvl::Full,
+                        strategy,
+                        ..Default::default()
+                    },
+                )
+                .collect::<HashSet<_>>();
+
+                assert_eq!(chain_scc_set, fwd_bwd_scc_set);
+            }
+        }
+    }
+
+    #[test_resources("./models/bbm-inputs-true/*.aeon")]
+    fn compare_semantics_chain_fwd_bwd_selected(model_path: &str) {
+        compare_semantics_with_fwd_bwd(model_path);
+    }
+}
@@ -0,0 +1,285 @@
+//! A memory-bounded variant of the chain decomposition.
+//!
+//! The pending entries of the work stack are the restrictions (the sets of states of the
+//! subgraphs to be decomposed) and the hints. Once the BDDs of the pending entries exceed
+//! a memory budget, the oldest entries (which are popped last) are spilled into files in
+//! a directory and read back when they reach the top of the stack.
+
+use crate::assert_precondition_graph_not_colored;
+use crate::chain::TrimLvl;
+use crate::chain::chain_step;
+use crate::checkpoint::read_bdd;
+use crate::checkpoint::write_bdd;
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicContext;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// The number of spilling stacks created by this process (used to name their directories).
+static NUM_STACKS: AtomicUsize = AtomicUsize::new(0);
+
+/// A `(restriction, hint)` pair of the work stack.
+type Entry = (GraphColoredVertices, GraphColoredVertices);
+
+/// The chain decomposition of the `graph` (like [crate::chain] with
+/// [crate::chain::Strategy::Chain]) which keeps at most (roughly) `budget` bytes of pending
+/// work in memory and spills the rest into files in the `directory`.
+///
+/// The size of a pending entry is the size of its BDDs in the binary format (10 bytes per
+/// node). The topmost entry is always kept in memory, even if it alone exceeds the budget.
+/// The `directory` is created if needed; the spilled files are written into a fresh
+/// subdirectory of it (so that concurrent decompositions can share the `directory`),
+/// which is removed once the decomposition finishes (or fails).
+///
+/// Only the plain chain decomposition is supported; the other options of
+/// [crate::chain::Config] (the strategy, the preprocessing, splitting by the invariant
+/// variables) are not available in this mode.
+pub fn chain_with_memory_budget<G: SymbolicTransitionSystem>(
+    graph: &G,
+    trim_lvl: TrimLvl,
+    budget: usize,
+    directory: &Path,
+) -> Result<Vec<GraphColoredVertices>, String> {
+    assert_precondition_graph_not_colored(graph);
+
+    let restrictor = trim_lvl.restrictor();
+    let mut stack = SpillingStack::new(graph.symbolic_context(), budget, directory)?;
+    let initial = trim_lvl.initial_states(graph, graph.unit_colored_vertices().clone());
+    if !initial.is_empty() {
+        stack.push((initial, graph.empty_colored_vertices().clone()))?;
+    }
+
+    let mut output = Vec::new();
+    while let Some((restriction, hint)) = stack.pop()? {
+        let mut pushed = Vec::new();
+        let the_scc = chain_step(graph, &restriction, hint, restrictor, &mut pushed);
+        for entry in pushed {
+            stack.push(entry)?;
+        }
+
+        if !the_scc.is_singleton() {
+            output.push(the_scc);
+        }
+    }
+
+    Ok(output)
+}
+
+/// A stack of entries whose bottom part is stored in files.
+///
+/// The spilled entries are always below the in-memory ones. The files are stored in
+/// a directory owned by the stack.
+struct SpillingStack<'a> {
+    context: &'a SymbolicContext,
+    budget: usize,
+    directory: PathBuf,
+    /// The in-memory entries; the top of the stack is at the back.
+    in_memory: VecDeque<Entry>,
+    /// The total size of the in-memory entries.
+    in_memory_size: usize,
+    /// The files of the spilled entries; the last one is spilled most recently.
+    spilled: Vec<PathBuf>,
+    next_file: usize,
+}
+
+impl<'a> SpillingStack<'a> {
+    fn new(context: &'a SymbolicContext, budget: usize, directory: &Path) -> Result<Self, String> {
+        let id = NUM_STACKS.fetch_add(1, Ordering::Relaxed);
+        let directory = directory.join(format!("spill-{}-{id}", std::process::id()));
+        std::fs::create_dir_all(&directory).map_err(|e| e.to_string())?;
+        Ok(SpillingStack {
+            context,
+            budget,
+            directory,
+            in_memory: VecDeque::new(),
+            in_memory_size: 0,
+            spilled: Vec::new(),
+            next_file: 0,
+        })
+    }
+
+    fn push(&mut self, entry: Entry) -> Result<(), String> {
+        self.in_memory_size += entry_size(&entry);
+        self.in_memory.push_back(entry);
+
+        while self.in_memory_size > self.budget && self.in_memory.len() > 1 {
+            let oldest = self.in_memory.pop_front().unwrap();
+            self.in_memory_size -= entry_size(&oldest);
+            self.spill(&oldest)?;
+        }
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Option<Entry>, String> {
+        if let Some(entry) = self.in_memory.pop_back() {
+            self.in_memory_size -= entry_size(&entry);
+            return Ok(Some(entry));
+        }
+
+        match self.spilled.pop() {
+            None => Ok(None),
+            Some(path) => {
+                let entry = self.read(&path);
+                std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+                entry.map(Some)
+            }
+        }
+    }
+
+    fn spill(&mut self, entry: &Entry) -> Result<(), String> {
+        let path = self.directory.join(format!("entry-{}.bdd", self.next_file));
+        self.next_file += 1;
+
+        let file = File::create(&path).map_err(|e| e.to_string())?;
+        // from now on, the file is removed even if the writing fails
+        self.spilled.push(path);
+
+        let mut output = BufWriter::new(file);
+        write_bdd(&mut output, entry.0.as_bdd()).map_err(|e| e.to_string())?;
+        write_bdd(&mut output, entry.1.as_bdd()).map_err(|e| e.to_string())?;
+        output.flush().map_err(|e| e.to_string())
+    }
+
+    fn read(&self, path: &Path) -> Result<Entry, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        let mut input = BufReader::new(file);
+        let num_vars = self.context.bdd_variable_set().num_vars();
+        let restriction = read_bdd(&mut input, num_vars)?;
+        let hint = read_bdd(&mut input, num_vars)?;
+        Ok((
+            GraphColoredVertices::new(restriction, self.context),
+            GraphColoredVertices::new(hint, self.context),
+        ))
+    }
+}
+
+/// Removes the files of the entries that were never read back, and the directory.
+impl Drop for SpillingStack<'_> {
+    fn drop(&mut self) {
+        for path in &self.spilled {
+            let _ = std::fs::remove_file(path);
+        }
+        let _ = std::fs::remove_dir(&self.directory);
+    }
+}
+
+fn entry_size(entry: &Entry) -> usize {
+    10 * (entry.0.as_bdd().size() + entry.1.as_bdd().size())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Config;
+    use crate::chain::chain;
+    use crate::test_utils::skip_large_model;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+    use test_generator::test_resources;
+
+    fn spill_directory(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("scc-spilling-{}-{name}", std::process::id()))
+    }
+
+    fn num_files(directory: &Path) -> usize {
+        std::fs::read_dir(directory).unwrap().count()
+    }
+
+    #[test]
+    fn spilling_stack_order() {
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> A
+            B -> B
+            $A: A
+            $B: B
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let entries = [
+            (
+                graph.unit_colored_vertices().clone(),
+                graph.mk_empty_colored_vertices(),
+            ),
+            (
+                graph.mk_subspace(&[(var_a, true)]),
+                graph.mk_subspace(&[(var_a, true), (var_b, false)]),
+            ),
+            (
+                graph.mk_subspace(&[(var_b, false)]),
+                graph.mk_empty_colored_vertices(),
+            ),
+        ];
+
+        let directory = spill_directory("order");
+        let mut stack = SpillingStack::new(graph.symbolic_context(), 0, &directory).unwrap();
+        for entry in &entries {
+            stack.push(entry.clone()).unwrap();
+        }
+        // only the topmost entry is kept in memory
+        assert_eq!(num_files(&stack.directory), 2);
+
+        // another stack in the same directory does not interfere
+        let mut other = SpillingStack::new(graph.symbolic_context(), 0, &directory).unwrap();
+        for entry in entries.iter().rev() {
+            other.push(entry.clone()).unwrap();
+        }
+        assert_eq!(num_files(&directory), 2);
+        drop(other);
+
+        for entry in entries.iter().rev() {
+            assert_eq!(stack.pop().unwrap().as_ref(), Some(entry));
+        }
+        assert_eq!(stack.pop().unwrap(), None);
+        assert_eq!(num_files(&stack.directory), 0);
+
+        // the files of the entries which are never popped are removed as well
+        for entry in &entries {
+            stack.push(entry.clone()).unwrap();
+        }
+        drop(stack);
+        assert_eq!(num_files(&directory), 0);
+
+        std::fs::remove_dir(&directory).unwrap();
+    }
+
+    #[test_resources("./models/bbm-inputs-true/*.aeon")]
+    fn compare_chain_with_memory_budget_with_chain(model_path: &str) {
+        let bn = BooleanNetwork::try_from_file(model_path).unwrap();
+        let bn = bn.inline_constants(true, true);
+
+        if skip_large_model(&bn, model_path) {
+            return;
+        }
+
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let name = Path::new(model_path).file_stem().unwrap().to_str().unwrap();
+        let directory = spill_directory(name);
+        for trim_lvl in [TrimLvl::None, TrimLvl::StartOnly, TrimLvl::Full] {
+            let config = Config {
+                trim_lvl,
+                ..Default::default()
+            };
+            let expected = chain(graph.clone(), config).collect::<Vec<_>>();
+
+            for budget in [0, 1_000, usize::MAX] {
+                let sccs = chain_with_memory_budget(&graph, trim_lvl, budget, &directory).unwrap();
+                assert_eq!(sccs, expected);
+                assert_eq!(num_files(&directory), 0);
+            }
+        }
+        std::fs::remove_dir(&directory).unwrap();
+    }
+}
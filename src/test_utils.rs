@@ -0,0 +1,77 @@
+//! Fixtures shared by the tests of multiple modules.
+
+use crate::random_network::RandomNetworkConfig;
+use crate::random_network::random_network;
+use biodivine_lib_param_bn::BooleanNetwork;
+use proptest::prelude::*;
+
+/// `A` and `B` cycle through all four states unless `C` is set, which then resets them.
+pub(crate) fn cycle_network() -> BooleanNetwork {
+    BooleanNetwork::try_from(
+        r#"
+        B -| A
+        C -| A
+        A -> B
+        C -| B
+        A -> C
+        B -> C
+        C -> C
+        $A: !B & !C
+        $B: A & !C
+        $C: C | (A & B)
+        "#,
+    )
+    .unwrap()
+}
+
+/// The largest number of variables of the models used by the (model-based) tests.
+///
+/// The threshold is raised with the `expensive-tests` feature.
+pub(crate) fn skip_threshold() -> usize {
+    if cfg!(feature = "expensive-tests") {
+        14
+    } else {
+        10
+    }
+}
+
+/// Whether the model is too large for the (model-based) tests (see [skip_threshold]);
+/// prints a note if so.
+pub(crate) fn skip_large_model(bn: &BooleanNetwork, model_path: &str) -> bool {
+    let skip_threshold = skip_threshold();
+
+    if bn.num_vars() > skip_threshold {
+        // The network is too large.
+        println!(
+            " >> [{} > {}] Skipping {}.",
+            bn.num_vars(),
+            skip_threshold,
+            model_path
+        );
+        return true;
+    }
+    false
+}
+
+/// Random networks with up to `max_vars` variables, for comparing the symbolic algorithms
+/// with the explicit oracle (see [random_network]).
+pub(crate) fn arb_network(max_vars: usize) -> impl Strategy<Value = BooleanNetwork> {
+    (
+        1..=max_vars,
+        1usize..=3,
+        0.0f64..=1.0,
+        0.0f64..=1.0,
+        any::<u64>(),
+    )
+        .prop_map(
+            |(num_vars, max_in_degree, activation_probability, cycle_density, seed)| {
+                let config = RandomNetworkConfig {
+                    num_vars,
+                    max_in_degree,
+                    activation_probability,
+                    cycle_density,
+                };
+                random_network(config, seed)
+            },
+        )
+}
@@ -0,0 +1,189 @@
+//! Abstraction over the symbolic transition systems the SCC decomposition works with.
+//!
+//! The algorithms only ever need a handful of symbolic operations (successors,
+//! predecessors, restriction to a subset of states, ...). These are captured by
+//! [SymbolicTransitionSystem], so that the same decomposition code can be used for
+//! the asynchronous semantics ([SymbolicAsyncGraph]) as well as for other update
+//! semantics (see [crate::semantics]).
+
+use biodivine_lib_param_bn::BooleanNetwork;
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+use biodivine_lib_param_bn::symbolic_async_graph::SymbolicContext;
+
+/// A (colored) transition system over the states of a Boolean network, represented
+/// symbolically.
+///
+/// All the sets passed to and returned by the methods are `GraphColoredVertices`
+/// of the [SymbolicContext] of the system. The transitions are never self-loops.
+pub trait SymbolicTransitionSystem: Clone {
+    /// The symbolic encoding of the states (and colors) of the system.
+    fn symbolic_context(&self) -> &SymbolicContext;
+
+    /// The set of all states of the system.
+    fn unit_colored_vertices(&self) -> &GraphColoredVertices;
+
+    /// The empty set of states of the system.
+    fn empty_colored_vertices(&self) -> &GraphColoredVertices;
+
+    /// The network variables of the system.
+    fn variables(&self) -> Vec<VariableId>;
+
+    /// The subsystem induced by the given `set` of states.
+    ///
+    /// Only the states of the `set` (and the transitions among them) are retained.
+    fn restrict(&self, set: &GraphColoredVertices) -> Self;
+
+    /// All the successors of the states in `initial`.
+    fn post(&self, initial: &GraphColoredVertices) -> GraphColoredVertices;
+
+    /// All the predecessors of the states in `initial`.
+    fn pre(&self, initial: &GraphColoredVertices) -> GraphColoredVertices;
+
+    /// The successors of the states in `initial` that are not in `initial`, reached
+    /// by a transition that changes the value of `var`.
+    fn var_post_out(&self, var: VariableId, initial: &GraphColoredVertices)
+    -> GraphColoredVertices;
+
+    /// The predecessors of the states in `initial` that are not in `initial`, reached
+    /// by a transition that changes the value of `var`.
+    fn var_pre_out(&self, var: VariableId, initial: &GraphColoredVertices) -> GraphColoredVertices;
+
+    /// The subset of `set` with at least one successor within `set`.
+    fn can_post_within(&self, set: &GraphColoredVertices) -> GraphColoredVertices;
+
+    /// The subset of `set` with at least one predecessor within `set`.
+    fn can_pre_within(&self, set: &GraphColoredVertices) -> GraphColoredVertices;
+
+    /// The successors of the states in `initial` that are in the `set`.
+    ///
+    /// For `initial` within the `set`, these are the successors within the subsystem induced
+    /// by the `set` (see [Self::restrict]), computed without building the subsystem.
+    /// The `initial` states outside of the `set` are not removed; e.g. the predecessors of
+    /// an SCC within the rest of the states are `pre_within(scc, rest)`.
+    fn post_within(
+        &self,
+        initial: &GraphColoredVertices,
+        set: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        self.post(initial).intersect(set)
+    }
+
+    /// The predecessors of the states in `initial` that are in the `set` (see
+    /// [Self::post_within]).
+    fn pre_within(
+        &self,
+        initial: &GraphColoredVertices,
+        set: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        self.pre(initial).intersect(set)
+    }
+
+    /// The states of [Self::var_post_out] that are in the `set` (see [Self::post_within]).
+    fn var_post_out_within(
+        &self,
+        var: VariableId,
+        initial: &GraphColoredVertices,
+        set: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        self.var_post_out(var, initial).intersect(set)
+    }
+
+    /// The states of [Self::var_pre_out] that are in the `set` (see [Self::post_within]).
+    fn var_pre_out_within(
+        &self,
+        var: VariableId,
+        initial: &GraphColoredVertices,
+        set: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        self.var_pre_out(var, initial).intersect(set)
+    }
+
+    /// The network the system was built from, if any.
+    ///
+    /// The trap spaces of the network are trap sets of the system under any update
+    /// semantics (see [crate::preprocessing]). Unknown by default.
+    fn as_network(&self) -> Option<&BooleanNetwork> {
+        None
+    }
+}
+
+/// The asynchronous semantics; simply delegates to the inherent methods.
+impl SymbolicTransitionSystem for SymbolicAsyncGraph {
+    fn symbolic_context(&self) -> &SymbolicContext {
+        SymbolicAsyncGraph::symbolic_context(self)
+    }
+
+    fn unit_colored_vertices(&self) -> &GraphColoredVertices {
+        SymbolicAsyncGraph::unit_colored_vertices(self)
+    }
+
+    fn empty_colored_vertices(&self) -> &GraphColoredVertices {
+        SymbolicAsyncGraph::empty_colored_vertices(self)
+    }
+
+    fn variables(&self) -> Vec<VariableId> {
+        SymbolicAsyncGraph::variables(self).collect()
+    }
+
+    fn restrict(&self, set: &GraphColoredVertices) -> Self {
+        SymbolicAsyncGraph::restrict(self, set)
+    }
+
+    fn post(&self, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        SymbolicAsyncGraph::post(self, initial)
+    }
+
+    fn pre(&self, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        SymbolicAsyncGraph::pre(self, initial)
+    }
+
+    fn var_post_out(
+        &self,
+        var: VariableId,
+        initial: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        SymbolicAsyncGraph::var_post_out(self, var, initial)
+    }
+
+    fn var_pre_out(&self, var: VariableId, initial: &GraphColoredVertices) -> GraphColoredVertices {
+        SymbolicAsyncGraph::var_pre_out(self, var, initial)
+    }
+
+    /// Intersects the successors over every variable separately, so that the (possibly
+    /// large) successors outside of the `set` are never united.
+    fn post_within(
+        &self,
+        initial: &GraphColoredVertices,
+        set: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        SymbolicAsyncGraph::variables(self).fold(self.mk_empty_colored_vertices(), |acc, var| {
+            acc.union(&self.var_post(var, initial).intersect(set))
+        })
+    }
+
+    /// Same as [Self::post_within].
+    fn pre_within(
+        &self,
+        initial: &GraphColoredVertices,
+        set: &GraphColoredVertices,
+    ) -> GraphColoredVertices {
+        SymbolicAsyncGraph::variables(self).fold(self.mk_empty_colored_vertices(), |acc, var| {
+            acc.union(&self.var_pre(var, initial).intersect(set))
+        })
+    }
+
+    fn can_post_within(&self, set: &GraphColoredVertices) -> GraphColoredVertices {
+        SymbolicAsyncGraph::can_post_within(self, set)
+    }
+
+    fn can_pre_within(&self, set: &GraphColoredVertices) -> GraphColoredVertices {
+        SymbolicAsyncGraph::can_pre_within(self, set)
+    }
+
+    fn as_network(&self) -> Option<&BooleanNetwork> {
+        SymbolicAsyncGraph::as_network(self)
+    }
+}
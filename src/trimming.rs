@@ -5,7 +5,8 @@
 //!
 use biodivine_lib_param_bn::biodivine_std::traits::Set;
 use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
-use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+use crate::transition_system::SymbolicTransitionSystem;
 
 /// Component of trimming the *easy to detect* trivial SCCs.
 ///
@@ -20,7 +21,10 @@ use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
 ///
 /// (A trivial SCC may still be between two non-trivial SCCs - such will not be
 /// detected, hence *overapproximation*)
-fn trim_trailing(graph: &SymbolicAsyncGraph, set: GraphColoredVertices) -> GraphColoredVertices {
+fn trim_trailing<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: GraphColoredVertices,
+) -> GraphColoredVertices {
     match set.is_empty() {
         true => set,
         false => {
@@ -48,7 +52,10 @@ fn trim_trailing(graph: &SymbolicAsyncGraph, set: GraphColoredVertices) -> Graph
 ///
 /// (A trivial SCC may still be between two non-trivial SCCs - such will not be
 /// detected, hence *overapproximation*)
-fn trim_leading(graph: &SymbolicAsyncGraph, set: GraphColoredVertices) -> GraphColoredVertices {
+fn trim_leading<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: GraphColoredVertices,
+) -> GraphColoredVertices {
     match set.is_empty() {
         true => set,
         false => {
@@ -76,6 +83,9 @@ fn trim_leading(graph: &SymbolicAsyncGraph, set: GraphColoredVertices) -> GraphC
 ///
 /// (A trivial SCC may still be between two non-trivial SCCs - such will not be
 /// detected, hence *overapproximation*)
-pub(crate) fn trim(graph: &SymbolicAsyncGraph, set: GraphColoredVertices) -> GraphColoredVertices {
+pub(crate) fn trim<G: SymbolicTransitionSystem>(
+    graph: &G,
+    set: GraphColoredVertices,
+) -> GraphColoredVertices {
     trim_trailing(graph, trim_leading(graph, set))
 }
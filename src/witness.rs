@@ -0,0 +1,210 @@
+//! Extraction of concrete witnesses: shortest paths and cycles as explicit sequences of states.
+//!
+//! The witnesses are computed from the forward BFS layers (`post` minus the states seen so far),
+//! walking the layers back using `pre` once the target is hit.
+
+use crate::transition_system::SymbolicTransitionSystem;
+use biodivine_lib_param_bn::VariableId;
+use biodivine_lib_param_bn::biodivine_std::traits::Set;
+use biodivine_lib_param_bn::symbolic_async_graph::GraphColoredVertices;
+
+/// A sequence of states (singletons), every state being a successor of the previous one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Witness {
+    pub states: Vec<GraphColoredVertices>,
+    /// The variables flipped by every step (`flipped[i]` leads from `states[i]` to the next one).
+    pub flipped: Vec<Vec<VariableId>>,
+}
+
+impl Witness {
+    fn new<G: SymbolicTransitionSystem>(graph: &G, states: Vec<GraphColoredVertices>) -> Self {
+        let flipped = states
+            .windows(2)
+            .map(|step| flipped_variables(graph, &step[0], &step[1]))
+            .collect();
+
+        Witness { states, flipped }
+    }
+
+    /// The number of transitions.
+    pub fn len(&self) -> usize {
+        self.flipped.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flipped.is_empty()
+    }
+}
+
+/// A shortest path from some state of `from` to some state of `to`.
+///
+/// Returns `None` if there is no such path; a path of a single state if the sets intersect.
+pub fn shortest_path<G: SymbolicTransitionSystem>(
+    graph: &G,
+    from: &GraphColoredVertices,
+    to: &GraphColoredVertices,
+) -> Option<Witness> {
+    layered_path(graph, from, to).map(|states| Witness::new(graph, states))
+}
+
+/// A shortest cycle through the `state` within the `scc` (which must contain the `state`).
+///
+/// The first and the last state of the witness are both `state`. Returns `None` if there is
+/// no such cycle, i.e. the `scc` is trivial.
+pub fn shortest_cycle<G: SymbolicTransitionSystem>(
+    graph: &G,
+    state: &GraphColoredVertices,
+    scc: &GraphColoredVertices,
+) -> Option<Witness> {
+    assert!(state.is_singleton());
+    assert!(state.is_subset(scc));
+
+    let graph = graph.restrict(scc);
+    let successors = graph.post(state);
+    let mut states = layered_path(&graph, &successors, state)?;
+    states.insert(0, state.clone());
+
+    Some(Witness::new(&graph, states))
+}
+
+/// The variables whose values differ between two singleton states.
+pub(crate) fn flipped_variables<G: SymbolicTransitionSystem>(
+    graph: &G,
+    source: &GraphColoredVertices,
+    target: &GraphColoredVertices,
+) -> Vec<VariableId> {
+    let is_true = |state: &GraphColoredVertices, var: VariableId| {
+        !state.fix_network_variable(var, true).is_empty()
+    };
+    graph
+        .variables()
+        .into_iter()
+        .filter(|var| is_true(source, *var) != is_true(target, *var))
+        .collect()
+}
+
+/// A shortest path (as a sequence of singletons) from `from` to `to`.
+fn layered_path<G: SymbolicTransitionSystem>(
+    graph: &G,
+    from: &GraphColoredVertices,
+    to: &GraphColoredVertices,
+) -> Option<Vec<GraphColoredVertices>> {
+    let mut fwd_reachable_acc = from.clone();
+    let mut layers = vec![from.clone()];
+    while layers.last().unwrap().intersect(to).is_empty() {
+        // take only the *proper* layer
+        let next_layer = graph.post(layers.last().unwrap()).minus(&fwd_reachable_acc);
+
+        if next_layer.is_empty() {
+            return None;
+        }
+
+        fwd_reachable_acc = fwd_reachable_acc.union(&next_layer);
+        layers.push(next_layer);
+    }
+
+    // walk the layers back, always picking a predecessor of the state picked last
+    let mut path = vec![layers.pop().unwrap().intersect(to).pick_singleton()];
+    while let Some(layer) = layers.pop() {
+        let predecessors = graph.pre(path.last().unwrap()).intersect(&layer);
+        path.push(predecessors.pick_singleton());
+    }
+    path.reverse();
+
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantics::SymbolicRelationGraph;
+    use crate::test_utils::cycle_network;
+    use biodivine_lib_param_bn::BooleanNetwork;
+    use biodivine_lib_param_bn::symbolic_async_graph::SymbolicAsyncGraph;
+
+    #[test]
+    fn witness_shortest_path() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+        let state = |a, b, c| graph.mk_subspace(&[(var_a, a), (var_b, b), (var_c, c)]);
+
+        // 000 -> 100 -> 110 -> 111 -> (011 or 101) -> 001
+        let from = state(false, false, false);
+        let to = state(false, false, true);
+        let witness = shortest_path(&graph, &from, &to).unwrap();
+
+        assert_eq!(witness.len(), 5);
+        assert_eq!(witness.states.first(), Some(&from));
+        assert_eq!(witness.states.last(), Some(&to));
+        for (i, step) in witness.states.windows(2).enumerate() {
+            assert!(step[1].is_subset(&graph.post(&step[0])));
+            assert_eq!(witness.flipped[i].len(), 1);
+        }
+        assert_eq!(witness.flipped[0], vec![var_a]);
+        assert_eq!(witness.flipped[2], vec![var_c]);
+
+        // trivial and impossible paths
+        let witness = shortest_path(&graph, &to, &to).unwrap();
+        assert!(witness.is_empty());
+        assert_eq!(witness.states, vec![to.clone()]);
+        assert!(shortest_path(&graph, &to, &from).is_none());
+    }
+
+    #[test]
+    fn witness_shortest_cycle() {
+        let bn = cycle_network();
+        let graph = SymbolicAsyncGraph::new(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+        let var_c = bn.as_graph().find_variable("C").unwrap();
+
+        let scc = graph.mk_subspace(&[(var_c, false)]);
+        let state = graph.mk_subspace(&[(var_a, false), (var_b, false), (var_c, false)]);
+        let witness = shortest_cycle(&graph, &state, &scc).unwrap();
+
+        assert_eq!(witness.len(), 4);
+        assert_eq!(witness.states.first(), Some(&state));
+        assert_eq!(witness.states.last(), Some(&state));
+        assert_eq!(
+            witness.flipped,
+            vec![vec![var_a], vec![var_b], vec![var_a], vec![var_b]]
+        );
+
+        // the fixed point is not on any cycle
+        let fixed_point = graph.mk_subspace(&[(var_a, false), (var_b, false), (var_c, true)]);
+        assert!(shortest_cycle(&graph, &fixed_point, &fixed_point).is_none());
+    }
+
+    #[test]
+    fn witness_synchronous_cycle() {
+        // synchronously, `A` and `B` swap values
+        let bn = BooleanNetwork::try_from(
+            r#"
+            A -> B
+            B -> A
+            $A: B
+            $B: A
+            "#,
+        )
+        .unwrap();
+        let graph = SymbolicRelationGraph::synchronous(&bn).unwrap();
+        let var_a = bn.as_graph().find_variable("A").unwrap();
+        let var_b = bn.as_graph().find_variable("B").unwrap();
+
+        let state = graph
+            .unit_colored_vertices()
+            .fix_network_variable(var_a, true)
+            .fix_network_variable(var_b, false);
+        let scc = graph.post(&state).union(&state);
+        let witness = shortest_cycle(&graph, &state, &scc).unwrap();
+
+        assert_eq!(witness.len(), 2);
+        assert_eq!(
+            witness.flipped,
+            vec![vec![var_a, var_b], vec![var_a, var_b]]
+        );
+    }
+}